@@ -0,0 +1,380 @@
+//! Synchronous equivalents of the lookups, for programs that do not run an async executor.
+//! Every call runs the async lookup to completion on a runtime owned by the client, so they
+//! must not be called from within an async context
+
+use futures::{Stream, StreamExt};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::runtime::{Builder, Runtime};
+
+use crate::client;
+use crate::error::Error;
+use crate::utils::disk_cache::OfflineHttpClient;
+use crate::utils::http_client::{HttpClient, HttpClientTrait};
+use crate::youtube::batch::BatchLookup;
+use crate::youtube::bulk::{BulkOptions, BulkStream, Progress};
+use crate::youtube::channel::Channel;
+use crate::youtube::channel_id_store::ChannelIdStore;
+use crate::youtube::comment::{
+    Comment, CommentCursor, CommentFeed, CommentReplyOptions, CommentThread, CommentThreadOptions,
+    CommentTree, TextFormat,
+};
+use crate::youtube::id::{ChannelId, Handle, PlaylistId, VideoId};
+use crate::youtube::link::{Resolved, YoutubeUrl};
+use crate::youtube::listing::{ListingOptions, VideoListing};
+use crate::youtube::page::Page;
+use crate::youtube::parts::RequestOptions;
+use crate::youtube::playlist::Playlist;
+use crate::youtube::video::Video;
+
+/// Blocking version of `youtube_search::YoutubeClient`
+pub struct YoutubeClient<T: HttpClientTrait = HttpClient> {
+    inner: client::YoutubeClient<T>,
+    runtime: Runtime,
+}
+
+impl Default for YoutubeClient {
+    fn default() -> Self {
+        YoutubeClient::new()
+    }
+}
+
+impl YoutubeClient {
+    pub fn new() -> Self {
+        YoutubeClient::with_http_client(HttpClient::new())
+    }
+}
+
+impl YoutubeClient<OfflineHttpClient> {
+    /// Never touches the network, see `youtube_search::YoutubeClient::offline`
+    pub fn offline(directory: impl Into<PathBuf>) -> Self {
+        YoutubeClient::from_async(client::YoutubeClient::offline(directory))
+    }
+}
+
+impl<T: HttpClientTrait + 'static> YoutubeClient<T> {
+    pub fn with_http_client(client: T) -> Self {
+        YoutubeClient::from_async(client::YoutubeClient::with_http_client(client))
+    }
+
+    /// Runs the lookups of an async client, keeping its http client and channel id store
+    pub fn from_async(inner: client::YoutubeClient<T>) -> Self {
+        // Like `reqwest::blocking`, a runtime that cannot start is not recoverable
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to start the runtime of the blocking client");
+
+        YoutubeClient { inner, runtime }
+    }
+
+    /// Remembers the channel of every resolved handle in the store
+    pub fn with_channel_id_store(mut self, store: impl ChannelIdStore + 'static) -> Self {
+        self.inner = self.inner.with_channel_id_store(store);
+        self
+    }
+
+    /// The http client, to read its cache statistics
+    pub fn http_client(&self) -> Arc<T> {
+        self.inner.http_client()
+    }
+
+    /// Find a youtube channel by handle: https://www.youtube.com/@{handle}
+    pub fn find_youtube_channel(&self, handle: &Handle) -> Result<Channel, Error> {
+        self.runtime
+            .block_on(self.inner.find_youtube_channel(handle))
+    }
+
+    /// Find many youtube channels by handle within the concurrency and rate limits of the
+    /// options, each handle is yielded with its result as soon as its lookup finishes
+    pub fn find_youtube_channels(
+        &self,
+        handles: Vec<Handle>,
+        options: &BulkOptions,
+    ) -> BulkIter<'_, Handle, Channel> {
+        BulkIter {
+            stream: self.inner.find_youtube_channels(handles, options),
+            runtime: RuntimeRef::Borrowed(&self.runtime),
+        }
+    }
+
+    /// Like `find_youtube_channels`, the iterator takes over the runtime of the client
+    fn into_youtube_channels(
+        self,
+        handles: Vec<Handle>,
+        options: &BulkOptions,
+    ) -> BulkIter<'static, Handle, Channel> {
+        BulkIter {
+            stream: self.inner.find_youtube_channels(handles, options),
+            runtime: RuntimeRef::Owned(self.runtime),
+        }
+    }
+
+    /// Find latest videos from a channel, will return an error if the channel has no videos
+    pub fn find_latest_videos(&self, channel: &Channel, count: i32) -> Result<Vec<Video>, Error> {
+        self.runtime
+            .block_on(self.inner.find_latest_videos(channel, count))
+    }
+
+    /// Find latest videos from a channel along with the items that could not be parsed
+    pub fn find_latest_videos_with_options(
+        &self,
+        channel: &Channel,
+        count: i32,
+        options: &ListingOptions,
+    ) -> Result<VideoListing, Error> {
+        self.runtime.block_on(
+            self.inner
+                .find_latest_videos_with_options(channel, count, options),
+        )
+    }
+
+    /// Find a specific video on the platform by its id
+    pub fn find_video(&self, video_id: &VideoId) -> Result<Video, Error> {
+        self.runtime.block_on(self.inner.find_video(video_id))
+    }
+
+    /// Find a video with only the parts and fields of the options
+    pub fn find_video_with_options(
+        &self,
+        video_id: &VideoId,
+        options: &RequestOptions,
+    ) -> Result<Video, Error> {
+        self.runtime
+            .block_on(self.inner.find_video_with_options(video_id, options))
+    }
+
+    /// Find many videos at once, in the order of their ids, along with the ids that do not exist
+    pub fn find_videos(&self, video_ids: &[VideoId]) -> Result<BatchLookup<VideoId, Video>, Error> {
+        self.runtime.block_on(self.inner.find_videos(video_ids))
+    }
+
+    /// Find many videos at once with only the parts and fields of the options
+    pub fn find_videos_with_options(
+        &self,
+        video_ids: &[VideoId],
+        options: &RequestOptions,
+    ) -> Result<BatchLookup<VideoId, Video>, Error> {
+        self.runtime
+            .block_on(self.inner.find_videos_with_options(video_ids, options))
+    }
+
+    /// Find many channels at once, in the order of their ids, along with the ids that do not exist
+    pub fn find_channels(
+        &self,
+        channel_ids: &[ChannelId],
+    ) -> Result<BatchLookup<ChannelId, Channel>, Error> {
+        self.runtime.block_on(self.inner.find_channels(channel_ids))
+    }
+
+    /// Find many playlists at once, in the order of their ids, along with the ids that do not exist
+    pub fn find_playlists(
+        &self,
+        playlist_ids: &[PlaylistId],
+    ) -> Result<BatchLookup<PlaylistId, Playlist>, Error> {
+        self.runtime
+            .block_on(self.inner.find_playlists(playlist_ids))
+    }
+
+    /// Find a page of the comment threads of a video
+    pub fn find_comment_threads(
+        &self,
+        video: &Video,
+        options: &CommentThreadOptions,
+    ) -> Result<Page<CommentThread>, Error> {
+        self.runtime
+            .block_on(self.inner.find_comment_threads(video, options))
+    }
+
+    /// Find a page of the comment threads of every video of a channel, newest first
+    pub fn find_channel_comment_threads(
+        &self,
+        channel: &Channel,
+        options: &CommentThreadOptions,
+    ) -> Result<Page<CommentThread>, Error> {
+        self.runtime
+            .block_on(self.inner.find_channel_comment_threads(channel, options))
+    }
+
+    /// Find the comment threads posted on the videos of a channel since the cursor, along
+    /// with the cursor to poll from next time
+    pub fn find_channel_comment_threads_since(
+        &self,
+        channel: &Channel,
+        cursor: &CommentCursor,
+        options: &CommentThreadOptions,
+    ) -> Result<CommentFeed, Error> {
+        self.runtime.block_on(
+            self.inner
+                .find_channel_comment_threads_since(channel, cursor, options),
+        )
+    }
+
+    /// Find a page of the replies to a top level comment
+    pub fn find_comment_replies(
+        &self,
+        comment: &Comment,
+        options: &CommentReplyOptions,
+    ) -> Result<Page<Comment>, Error> {
+        self.runtime
+            .block_on(self.inner.find_comment_replies(comment, options))
+    }
+
+    /// Find every reply of a comment thread, going through all their pages
+    pub fn find_comment_tree(
+        &self,
+        thread: &CommentThread,
+        text_format: TextFormat,
+    ) -> Result<CommentTree, Error> {
+        self.runtime
+            .block_on(self.inner.find_comment_tree(thread, text_format))
+    }
+
+    /// Find the video, channel or playlist videos a youtube url points to
+    pub fn resolve_url(&self, url: &YoutubeUrl) -> Result<Resolved, Error> {
+        self.runtime.block_on(self.inner.resolve_url(url))
+    }
+}
+
+/// Results of a bulk operation, each call to `next` blocks until the next lookup finishes
+pub struct BulkIter<'a, K, T> {
+    stream: BulkStream<K, T>,
+    runtime: RuntimeRef<'a>,
+}
+
+/// Runtime of a client, or its own one when the iterator outlives a temporary client
+enum RuntimeRef<'a> {
+    Borrowed(&'a Runtime),
+    Owned(Runtime),
+}
+
+impl RuntimeRef<'_> {
+    fn get(&self) -> &Runtime {
+        match self {
+            RuntimeRef::Borrowed(runtime) => runtime,
+            RuntimeRef::Owned(runtime) => runtime,
+        }
+    }
+}
+
+impl<K, T> BulkIter<'_, K, T> {
+    pub fn progress(&self) -> Progress {
+        self.stream.progress()
+    }
+}
+
+impl<K, T> Iterator for BulkIter<'_, K, T> {
+    type Item = (K, Result<T, Error>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.runtime.get().block_on(self.stream.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+/// Find a youtube channel by handle: https://www.youtube.com/@{handle}
+pub fn find_youtube_channel(handle: &Handle) -> Result<Channel, Error> {
+    YoutubeClient::new().find_youtube_channel(handle)
+}
+
+/// Find many youtube channels by handle within the concurrency and rate limits of the options,
+/// the returned iterator yields each handle with its result and reports its progress
+pub fn find_youtube_channels(
+    handles: Vec<Handle>,
+    options: &BulkOptions,
+) -> BulkIter<'static, Handle, Channel> {
+    YoutubeClient::new().into_youtube_channels(handles, options)
+}
+
+/// Find latest videos from a channel, will return an error if the channel has no videos
+pub fn find_latest_videos(channel: &Channel, count: i32) -> Result<Vec<Video>, Error> {
+    YoutubeClient::new().find_latest_videos(channel, count)
+}
+
+/// Find latest videos from a channel along with the items that could not be parsed,
+/// in strict mode the first invalid item fails the whole call
+pub fn find_latest_videos_with_options(
+    channel: &Channel,
+    count: i32,
+    options: &ListingOptions,
+) -> Result<VideoListing, Error> {
+    YoutubeClient::new().find_latest_videos_with_options(channel, count, options)
+}
+
+/// Find a specific video on the platform by its id, will return an error if the video does not exist
+pub fn find_video(video_id: &VideoId) -> Result<Video, Error> {
+    YoutubeClient::new().find_video(video_id)
+}
+
+/// Find many videos at once, 50 per request, along with the ids that do not exist
+pub fn find_videos(video_ids: &[VideoId]) -> Result<BatchLookup<VideoId, Video>, Error> {
+    YoutubeClient::new().find_videos(video_ids)
+}
+
+/// Find many channels at once, 50 per request, along with the ids that do not exist
+pub fn find_channels(channel_ids: &[ChannelId]) -> Result<BatchLookup<ChannelId, Channel>, Error> {
+    YoutubeClient::new().find_channels(channel_ids)
+}
+
+/// Find many playlists at once, 50 per request, along with the ids that do not exist
+pub fn find_playlists(
+    playlist_ids: &[PlaylistId],
+) -> Result<BatchLookup<PlaylistId, Playlist>, Error> {
+    YoutubeClient::new().find_playlists(playlist_ids)
+}
+
+/// Find the video, channel or playlist videos a youtube url points to
+pub fn resolve_url(url: &YoutubeUrl) -> Result<Resolved, Error> {
+    YoutubeClient::new().resolve_url(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{MockHttpClient, MockResponse};
+
+    #[test]
+    fn lookups_run_without_an_async_context() {
+        let video_response = r#"{"items": [{"snippet": {
+            "publishedAt": "2009-10-25T06:57:33Z",
+            "title": "Video Title",
+            "description": "Video Description"
+        }}]}"#;
+        let client = YoutubeClient::with_http_client(MockHttpClient::new(vec![
+            MockResponse::new(404, ""),
+            MockResponse::new(200, video_response),
+        ]));
+
+        let video = client.find_video(&"dQw4w9WgXcQ".parse().unwrap()).unwrap();
+        let missing = client.find_video(&"kJQP7kiw5Fk".parse().unwrap());
+
+        assert_eq!(video.title.as_deref(), Some("Video Title"));
+        assert!(matches!(missing, Err(Error::NotFound)));
+        assert_eq!(client.http_client().requested_urls().len(), 2);
+    }
+
+    #[test]
+    fn bulk_iterators_outlive_their_client() {
+        let search_response = r#"{"items": [{"snippet": {
+            "channelId": "id_channel1",
+            "channelTitle": "Channel title",
+            "channelHandle": "@channel1"
+        }}]}"#;
+        let client = YoutubeClient::with_http_client(MockHttpClient::new(vec![MockResponse::new(
+            200,
+            search_response,
+        )]));
+
+        let mut channels = client
+            .into_youtube_channels(vec!["channel1".parse().unwrap()], &BulkOptions::default());
+
+        let (handle, channel) = channels.next().unwrap();
+        assert_eq!(handle, "channel1");
+        assert_eq!(channel.unwrap().channel_id, "id_channel1");
+        assert!(channels.next().is_none());
+        assert_eq!(channels.progress().completed, 1);
+    }
+}
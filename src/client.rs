@@ -0,0 +1,295 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::error::Error;
+use crate::utils::disk_cache::{FileCacheStore, OfflineHttpClient};
+use crate::utils::http_client::{HttpClient, HttpClientTrait};
+use crate::youtube::batch::BatchLookup;
+use crate::youtube::bulk;
+use crate::youtube::bulk::{BulkOptions, BulkStream};
+use crate::youtube::channel::Channel;
+use crate::youtube::channel_id_store::ChannelIdStore;
+use crate::youtube::comment::{
+    Comment, CommentCursor, CommentFeed, CommentReplyOptions, CommentThread, CommentThreadOptions,
+    CommentTree, TextFormat,
+};
+use crate::youtube::id::{ChannelId, Handle, PlaylistId, VideoId};
+use crate::youtube::link::{Resolved, YoutubeUrl};
+use crate::youtube::listing::{ListingOptions, VideoListing};
+use crate::youtube::page::Page;
+use crate::youtube::parts::RequestOptions;
+use crate::youtube::playlist::Playlist;
+use crate::youtube::video::Video;
+
+/// Sends every lookup through the same http client, so a cache or the offline mode applies
+/// to all of them
+pub struct YoutubeClient<T: HttpClientTrait = HttpClient> {
+    client: Arc<T>,
+    channel_ids: Option<Arc<dyn ChannelIdStore>>,
+}
+
+impl Default for YoutubeClient {
+    fn default() -> Self {
+        YoutubeClient::new()
+    }
+}
+
+impl YoutubeClient {
+    pub fn new() -> Self {
+        YoutubeClient::with_http_client(HttpClient::new())
+    }
+}
+
+impl YoutubeClient<OfflineHttpClient> {
+    /// Never touches the network, answers only with the responses stored in a directory by a
+    /// `PersistentHttpClient` and fails with `Error::NotCached` otherwise
+    pub fn offline(directory: impl Into<PathBuf>) -> Self {
+        YoutubeClient::with_http_client(OfflineHttpClient::with_store(FileCacheStore::new(
+            directory,
+        )))
+    }
+}
+
+impl<T: HttpClientTrait + 'static> YoutubeClient<T> {
+    pub fn with_http_client(client: T) -> Self {
+        YoutubeClient {
+            client: Arc::new(client),
+            channel_ids: None,
+        }
+    }
+
+    /// Remembers the channel of every resolved handle in the store, and answers with it
+    /// instead of resolving the handle again while it is fresh
+    pub fn with_channel_id_store(mut self, store: impl ChannelIdStore + 'static) -> Self {
+        self.channel_ids = Some(Arc::new(store));
+        self
+    }
+
+    /// The http client, to call the model methods with it or read its cache statistics
+    pub fn http_client(&self) -> Arc<T> {
+        self.client.clone()
+    }
+
+    /// Find a youtube channel by handle: https://www.youtube.com/@{handle}
+    pub async fn find_youtube_channel(&self, handle: &Handle) -> Result<Channel, Error> {
+        match &self.channel_ids {
+            Some(store) => {
+                Channel::initialize_with_store(handle.clone(), store.as_ref(), self.client.clone())
+                    .await
+            }
+            None => Channel::initialize(handle.clone(), self.client.clone()).await,
+        }
+    }
+
+    /// Find many youtube channels by handle within the concurrency and rate limits of the
+    /// options, each handle is yielded with its result as soon as its lookup finishes
+    pub fn find_youtube_channels(
+        &self,
+        handles: Vec<Handle>,
+        options: &BulkOptions,
+    ) -> BulkStream<Handle, Channel> {
+        let Some(store) = self.channel_ids.clone() else {
+            return Channel::initialize_many(handles, options, self.client.clone());
+        };
+
+        let client = self.client.clone();
+        bulk::run(handles, options, move |handle| {
+            let (store, client) = (store.clone(), client.clone());
+            async move { Channel::initialize_with_store(handle, store.as_ref(), client).await }
+        })
+    }
+
+    /// Find latest videos from a channel, will return an error if the channel has no videos
+    pub async fn find_latest_videos(
+        &self,
+        channel: &Channel,
+        count: i32,
+    ) -> Result<Vec<Video>, Error> {
+        channel.get_latest_videos(count, self.client.clone()).await
+    }
+
+    /// Find latest videos from a channel along with the items that could not be parsed
+    pub async fn find_latest_videos_with_options(
+        &self,
+        channel: &Channel,
+        count: i32,
+        options: &ListingOptions,
+    ) -> Result<VideoListing, Error> {
+        channel
+            .get_latest_videos_with_options(count, options, self.client.clone())
+            .await
+    }
+
+    /// Find a specific video on the platform by its id
+    pub async fn find_video(&self, video_id: &VideoId) -> Result<Video, Error> {
+        Video::search_video_by_id(video_id.clone(), self.client.clone()).await
+    }
+
+    /// Find a video with only the parts and fields of the options
+    pub async fn find_video_with_options(
+        &self,
+        video_id: &VideoId,
+        options: &RequestOptions,
+    ) -> Result<Video, Error> {
+        Video::search_video_by_id_with_options(video_id.clone(), options, self.client.clone()).await
+    }
+
+    /// Find many videos at once, in the order of their ids, along with the ids that do not exist
+    pub async fn find_videos(
+        &self,
+        video_ids: &[VideoId],
+    ) -> Result<BatchLookup<VideoId, Video>, Error> {
+        Video::search_videos_by_ids(video_ids, self.client.clone()).await
+    }
+
+    /// Find many videos at once with only the parts and fields of the options
+    pub async fn find_videos_with_options(
+        &self,
+        video_ids: &[VideoId],
+        options: &RequestOptions,
+    ) -> Result<BatchLookup<VideoId, Video>, Error> {
+        Video::search_videos_by_ids_with_options(video_ids, options, self.client.clone()).await
+    }
+
+    /// Find many channels at once, in the order of their ids, along with the ids that do not exist
+    pub async fn find_channels(
+        &self,
+        channel_ids: &[ChannelId],
+    ) -> Result<BatchLookup<ChannelId, Channel>, Error> {
+        Channel::from_ids(channel_ids, self.client.clone()).await
+    }
+
+    /// Find many playlists at once, in the order of their ids, along with the ids that do not exist
+    pub async fn find_playlists(
+        &self,
+        playlist_ids: &[PlaylistId],
+    ) -> Result<BatchLookup<PlaylistId, Playlist>, Error> {
+        Playlist::from_ids(playlist_ids, self.client.clone()).await
+    }
+
+    /// Find a page of the comment threads of a video
+    pub async fn find_comment_threads(
+        &self,
+        video: &Video,
+        options: &CommentThreadOptions,
+    ) -> Result<Page<CommentThread>, Error> {
+        video.comment_threads(options, self.client.clone()).await
+    }
+
+    /// Find a page of the comment threads of every video of a channel, newest first
+    pub async fn find_channel_comment_threads(
+        &self,
+        channel: &Channel,
+        options: &CommentThreadOptions,
+    ) -> Result<Page<CommentThread>, Error> {
+        channel.comment_threads(options, self.client.clone()).await
+    }
+
+    /// Find the comment threads posted on the videos of a channel since the cursor, along
+    /// with the cursor to poll from next time
+    pub async fn find_channel_comment_threads_since(
+        &self,
+        channel: &Channel,
+        cursor: &CommentCursor,
+        options: &CommentThreadOptions,
+    ) -> Result<CommentFeed, Error> {
+        channel
+            .comment_threads_since(cursor, options, self.client.clone())
+            .await
+    }
+
+    /// Find a page of the replies to a top level comment
+    pub async fn find_comment_replies(
+        &self,
+        comment: &Comment,
+        options: &CommentReplyOptions,
+    ) -> Result<Page<Comment>, Error> {
+        comment.replies(options, self.client.clone()).await
+    }
+
+    /// Find every reply of a comment thread, going through all their pages
+    pub async fn find_comment_tree(
+        &self,
+        thread: &CommentThread,
+        text_format: TextFormat,
+    ) -> Result<CommentTree, Error> {
+        thread
+            .with_all_replies(text_format, self.client.clone())
+            .await
+    }
+
+    /// Find the video, channel or playlist videos a youtube url points to
+    pub async fn resolve_url(&self, url: &YoutubeUrl) -> Result<Resolved, Error> {
+        // Handles go through the channel id store
+        match url {
+            YoutubeUrl::Handle(handle) => self
+                .find_youtube_channel(handle)
+                .await
+                .map(Resolved::Channel),
+            _ => url.resolve(self.client.clone()).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{MockHttpClient, MockResponse};
+    use crate::utils::disk_cache::PersistentHttpClient;
+
+    #[tokio::test]
+    async fn offline_clients_answer_from_a_populated_directory() {
+        let directory = tempfile::tempdir().unwrap();
+        let video_response = r#"{"items": [{"snippet": {
+            "publishedAt": "2009-10-25T06:57:33Z",
+            "title": "Video Title",
+            "description": "Video Description"
+        }}]}"#;
+        let online = YoutubeClient::with_http_client(PersistentHttpClient::with_store(
+            MockHttpClient::new(vec![MockResponse::new(200, video_response)]),
+            FileCacheStore::new(directory.path()),
+        ));
+        let video_id: VideoId = "dQw4w9WgXcQ".parse().unwrap();
+        online.find_video(&video_id).await.unwrap();
+
+        let offline = YoutubeClient::offline(directory.path());
+
+        let video = offline.find_video(&video_id).await.unwrap();
+        assert_eq!(video.title.as_deref(), Some("Video Title"));
+        assert!(matches!(
+            offline
+                .find_youtube_channel(&"rickastleyyt".parse().unwrap())
+                .await,
+            Err(Error::NotCached { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn handle_urls_are_resolved_from_the_channel_id_store() {
+        use crate::youtube::channel_id_store::{MemoryChannelIdStore, StoredChannelId};
+
+        let store = MemoryChannelIdStore::new();
+        store
+            .save(
+                &"rickastleyyt".parse().unwrap(),
+                StoredChannelId::new(
+                    "UCuAXFkgsw1L7xaCfnd5JJOw".parse().unwrap(),
+                    "Rick Astley".to_string(),
+                ),
+            )
+            .unwrap();
+        let client = YoutubeClient::with_http_client(MockHttpClient::new(vec![]))
+            .with_channel_id_store(store);
+
+        let resolved = client
+            .resolve_url(&"https://www.youtube.com/@RickAstleyYT".parse().unwrap())
+            .await
+            .unwrap();
+
+        match resolved {
+            Resolved::Channel(channel) => assert_eq!(channel.title, "Rick Astley"),
+            _ => panic!("Expected a channel"),
+        }
+        assert!(client.http_client().requested_urls().is_empty());
+    }
+}
@@ -0,0 +1,96 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+
+use crate::youtube::id::IdError;
+use crate::youtube::link::UrlError;
+
+/// Every error returned by this crate, match on it to handle each case
+#[derive(Debug, Clone, Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// The video, channel or playlist does not exist
+    #[error("Resource not found")]
+    NotFound,
+    /// Too many requests were sent, wait `retry_after` before sending more when known
+    #[error("Rate limited by the API")]
+    RateLimited { retry_after: Option<Duration> },
+    /// The daily quota of the API has been spent
+    #[error("API quota exceeded")]
+    QuotaExceeded,
+    /// The API is down or overloaded, retrying later may succeed
+    #[error("API unavailable")]
+    Unavailable,
+    /// An id, handle, url or parameter is not valid
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+    /// The request could not be sent or its response could not be read
+    #[error("Network error: {0}")]
+    Network(#[source] Arc<reqwest::Error>),
+    /// The response does not have the expected format
+    #[error(transparent)]
+    Decode(Box<DecodeError>),
+    /// Any other error returned by the API
+    #[error(transparent)]
+    Api(ApiError),
+    /// The client is offline and the response of this url was never stored
+    #[error("Not cached: {url}")]
+    NotCached { url: String },
+}
+
+/// A response that could not be decoded, with enough context to find out why
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Failed to decode response from {url} (status {status}) at `{path}`: {message}")]
+pub struct DecodeError {
+    /// Url of the request, without the API key
+    pub url: String,
+    pub status: u16,
+    /// Path to the field that failed to decode, e.g. `items[0].snippet.title`
+    pub path: String,
+    pub message: String,
+    /// Beginning of the response body
+    pub body_excerpt: String,
+}
+
+/// Error returned by the API in its `{"error": {...}}` envelope
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub struct ApiError {
+    pub code: u16,
+    pub message: String,
+    /// Machine readable cause, e.g. `quotaExceeded` or `videoNotFound`
+    pub reason: Option<String>,
+    pub domain: Option<String>,
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "API error {}: {}", self.code, self.message)?;
+        if let Some(reason) = &self.reason {
+            write!(f, " ({})", reason)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error {
+    /// Whether the same request may succeed if it is sent again later
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Error::RateLimited { .. } | Error::Unavailable | Error::Network(_)
+        )
+    }
+}
+
+impl From<IdError> for Error {
+    fn from(error: IdError) -> Self {
+        Error::InvalidInput(error.to_string())
+    }
+}
+
+impl From<UrlError> for Error {
+    fn from(error: UrlError) -> Self {
+        Error::InvalidInput(error.to_string())
+    }
+}
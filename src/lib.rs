@@ -1,30 +1,110 @@
+#[cfg(feature = "blocking")]
+pub mod blocking;
+mod client;
+mod error;
 mod utils;
 mod youtube;
 
 #[cfg(test)]
 pub mod test_utils;
 
-use std::sync::Arc;
-use utils::http_client::{HttpClient, HttpClientTrait};
-use youtube::{
-    channel::{Channel, ChannelError},
-    video::{Video, VideoError},
+pub use client::YoutubeClient;
+pub use error::{ApiError, DecodeError, Error};
+pub use utils::{
+    cache::{CacheConfig, CacheStats, CachedHttpClient},
+    coalesce::CoalescingHttpClient,
+    disk_cache::{
+        CacheStore, FileCacheStore, OfflineHttpClient, PersistentHttpClient, PruneOptions,
+        StoredResponse,
+    },
+    http_client::{HttpClient, HttpClientTrait, HttpResponse},
 };
+pub use youtube::{
+    batch::{BatchLookup, SkippedItem},
+    bulk::{BulkOptions, BulkStream, Progress},
+    channel::{Channel, ChannelDetails},
+    channel_id_store::{
+        ChannelIdStore, JsonFileChannelIdStore, MemoryChannelIdStore, StoredChannelId,
+    },
+    comment::{
+        Comment, CommentCursor, CommentFeed, CommentOrder, CommentReplyOptions, CommentThread,
+        CommentThreadOptions, CommentTree, TextFormat,
+    },
+    id::{ChannelId, Handle, IdError, PlaylistId, VideoId},
+    link::{Resolved, UrlError, VideoUrlKind, YoutubeUrl},
+    listing::{
+        ItemError, ItemErrorKind, ListingOptions, ParseMode, UnavailablePolicy, VideoListing,
+    },
+    page::Page,
+    parts::{Part, RequestOptions},
+    playlist::Playlist,
+    thumbnail::{Thumbnail, ThumbnailSize, Thumbnails},
+    video::{
+        Availability, License, LiveBroadcastContent, LiveStreamingDetails, PrivacyStatus, Video,
+        VideoContentDetails, VideoStatistics, VideoStatus,
+    },
+};
+
+/// Find a youtube channel by handle: https://www.youtube.com/@{handle}, always with a request.
+/// Only a `YoutubeClient` given a store with `with_channel_id_store` remembers the channels
+pub async fn find_youtube_channel(handle: &Handle) -> Result<Channel, Error> {
+    YoutubeClient::new().find_youtube_channel(handle).await
+}
 
-/// Find a youtube channel by handle: https://www.youtube.com/user/@{handle}
-pub async fn find_youtube_channel(handle: &str) -> Result<Channel, ChannelError> {
-    let client = Arc::new(HttpClient::new());
-    Channel::initialize(handle.to_string(), client.clone()).await
+/// Find many youtube channels by handle within the concurrency and rate limits of the options,
+/// the returned stream yields each handle with its result and reports its progress
+pub fn find_youtube_channels(
+    handles: Vec<Handle>,
+    options: &BulkOptions,
+) -> BulkStream<Handle, Channel> {
+    YoutubeClient::new().find_youtube_channels(handles, options)
 }
 
 /// Find latest videos from a channel, will return an error if the channel has no videos
-pub async fn find_latest_videos(channel: &Channel, count: i32) -> Result<Vec<Video>, ChannelError> {
-    let client = Arc::new(HttpClient::new());
-    channel.get_latest_videos(count, client.clone()).await
+pub async fn find_latest_videos(channel: &Channel, count: i32) -> Result<Vec<Video>, Error> {
+    YoutubeClient::new()
+        .find_latest_videos(channel, count)
+        .await
+}
+
+/// Find latest videos from a channel along with the items that could not be parsed,
+/// in strict mode the first invalid item fails the whole call
+pub async fn find_latest_videos_with_options(
+    channel: &Channel,
+    count: i32,
+    options: &ListingOptions,
+) -> Result<VideoListing, Error> {
+    YoutubeClient::new()
+        .find_latest_videos_with_options(channel, count, options)
+        .await
 }
 
 /// Find a specific video on the platform by its id, will return an error if the video does not exist
-pub async fn find_video(video_id: String) -> Result<Video, VideoError> {
-    let client = Arc::new(HttpClient::new());
-    Video::search_video_by_id(video_id, client.clone()).await
+pub async fn find_video(video_id: &VideoId) -> Result<Video, Error> {
+    YoutubeClient::new().find_video(video_id).await
+}
+
+/// Find many videos at once, 50 per request, the videos keep the order of the ids and
+/// the ids that do not exist are reported instead of failing the whole batch
+pub async fn find_videos(video_ids: &[VideoId]) -> Result<BatchLookup<VideoId, Video>, Error> {
+    YoutubeClient::new().find_videos(video_ids).await
+}
+
+/// Find many channels at once, 50 per request, along with the ids that do not exist
+pub async fn find_channels(
+    channel_ids: &[ChannelId],
+) -> Result<BatchLookup<ChannelId, Channel>, Error> {
+    YoutubeClient::new().find_channels(channel_ids).await
+}
+
+/// Find many playlists at once, 50 per request, along with the ids that do not exist
+pub async fn find_playlists(
+    playlist_ids: &[PlaylistId],
+) -> Result<BatchLookup<PlaylistId, Playlist>, Error> {
+    YoutubeClient::new().find_playlists(playlist_ids).await
+}
+
+/// Find the video, channel or playlist videos a youtube url points to
+pub async fn resolve_url(url: &YoutubeUrl) -> Result<Resolved, Error> {
+    YoutubeClient::new().resolve_url(url).await
 }
@@ -1,49 +1,129 @@
-use crate::utils::http_client::HttpClientTrait;
+use crate::error::Error;
+use crate::utils::http_client::{HttpClientTrait, HttpResponse};
 use async_trait::async_trait;
-use reqwest::{Client, Error, Response};
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Clone)]
+pub struct MockResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+    delay: Option<Duration>,
+}
+
+impl MockResponse {
+    pub fn new(status: u16, body: &str) -> Self {
+        MockResponse {
+            status,
+            headers: Vec::new(),
+            body: body.to_owned(),
+            delay: None,
+        }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Waits before answering, so concurrent requests overlap
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+}
+
+/// Url and headers of a request sent to the mock
+type MockRequest = (String, Vec<(String, String)>);
 
 #[derive(Clone)]
 pub struct MockHttpClient {
-    client: Client,
-    responses: Arc<Mutex<Vec<String>>>,
+    responses: Arc<Mutex<Vec<MockResponse>>>,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
 }
 
 #[cfg(test)]
 impl MockHttpClient {
-    pub fn new(responses: Vec<String>) -> Self {
+    pub fn new(responses: Vec<MockResponse>) -> Self {
         MockHttpClient {
-            client: Client::new(),
             responses: Arc::new(Mutex::new(responses)),
+            requests: Arc::new(Mutex::new(Vec::new())),
         }
     }
+
+    /// Urls of every request sent so far, in order
+    pub fn requested_urls(&self) -> Vec<String> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(url, _)| url.clone())
+            .collect()
+    }
+
+    /// Headers sent with every request so far, in order
+    pub fn requested_headers(&self) -> Vec<Vec<(String, String)>> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, headers)| headers.clone())
+            .collect()
+    }
 }
 
 #[async_trait]
 impl HttpClientTrait for MockHttpClient {
     fn new() -> Self {
-        MockHttpClient::new(vec!["".to_owned()])
+        MockHttpClient::new(vec![MockResponse::new(200, "")])
     }
 
-    async fn get(&self, _url: &str) -> Result<Response, Error> {
-        let mut server = mockito::Server::new();
-
-        let mut responses = self.responses.lock().await;
-        let response = responses.pop().unwrap_or_default();
+    async fn get(&self, url: &str) -> Result<HttpResponse, Error> {
+        self.get_with_headers(url, &[]).await
+    }
 
-        let _mock = server
-            .mock("GET", "/")
-            .with_status(200)
-            .with_body(response.clone())
-            .create();
+    async fn get_with_headers(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> Result<HttpResponse, Error> {
+        self.requests.lock().unwrap().push((
+            url.to_owned(),
+            headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        ));
+        let response = self
+            .responses
+            .lock()
+            .unwrap()
+            .pop()
+            .unwrap_or_else(|| MockResponse::new(200, ""));
+        if let Some(delay) = response.delay {
+            crate::utils::runtime::sleep(delay).await;
+        }
 
-        self.client.get(&server.url()).send().await
+        Ok(response.headers.iter().fold(
+            HttpResponse::new(url, response.status, &response.body),
+            |http_response, (name, value)| http_response.with_header(name, value),
+        ))
     }
 }
 
 pub async fn create_client_with_responses(responses: Vec<&str>) -> Arc<MockHttpClient> {
-    Arc::new(MockHttpClient::new(
-        responses.into_iter().map(|s| s.to_string()).collect(),
-    ))
+    create_client_with_mock_responses(
+        responses
+            .into_iter()
+            .map(|body| MockResponse::new(200, body))
+            .collect(),
+    )
+    .await
+}
+
+pub async fn create_client_with_mock_responses(
+    responses: Vec<MockResponse>,
+) -> Arc<MockHttpClient> {
+    Arc::new(MockHttpClient::new(responses))
 }
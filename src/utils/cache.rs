@@ -0,0 +1,375 @@
+use async_trait::async_trait;
+use lru::LruCache;
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use url::Url;
+
+use super::http_client::{HttpClient, HttpClientTrait, HttpResponse};
+use crate::error::Error;
+
+/// How long responses are kept and how many of them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheConfig {
+    /// Maximum number of responses kept, the least recently used ones are evicted first
+    pub capacity: NonZeroUsize,
+    /// Time to live of the responses of endpoints without a specific one
+    pub default_ttl: Duration,
+    /// Time to live per endpoint, keyed by the last segment of the path, e.g. `videos`
+    pub ttls: HashMap<String, Duration>,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig {
+            capacity: NonZeroUsize::new(1000).unwrap(),
+            default_ttl: Duration::from_secs(5 * 60),
+            ttls: HashMap::from([
+                ("videos".to_owned(), Duration::from_secs(60 * 60)),
+                ("channels".to_owned(), Duration::from_secs(24 * 60 * 60)),
+                ("search".to_owned(), Duration::from_secs(24 * 60 * 60)),
+                ("playlistItems".to_owned(), Duration::from_secs(5 * 60)),
+            ]),
+        }
+    }
+}
+
+impl CacheConfig {
+    pub fn with_capacity(mut self, capacity: NonZeroUsize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    pub fn with_default_ttl(mut self, ttl: Duration) -> Self {
+        self.default_ttl = ttl;
+        self
+    }
+
+    /// Sets the time to live of an endpoint, a zero duration disables caching for it
+    pub fn with_ttl(mut self, endpoint: &str, ttl: Duration) -> Self {
+        self.ttls.insert(endpoint.to_owned(), ttl);
+        self
+    }
+
+    fn ttl(&self, url: &Url) -> Duration {
+        url.path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .and_then(|endpoint| self.ttls.get(endpoint))
+            .copied()
+            .unwrap_or(self.default_ttl)
+    }
+}
+
+/// Hits and misses of a cache since it was created
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Responses dropped to stay within the capacity
+    pub evictions: u64,
+    /// Responses currently kept, including expired ones not evicted yet
+    pub entries: usize,
+}
+
+impl CacheStats {
+    /// Share of the requests served from the cache, between 0 and 1
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+}
+
+struct CacheEntry {
+    response: HttpResponse,
+    expires_at: Instant,
+}
+
+struct CacheState {
+    entries: LruCache<String, CacheEntry>,
+    stats: CacheStats,
+}
+
+/// Keeps successful responses in memory and serves repeated requests from them
+pub struct CachedHttpClient<T: HttpClientTrait = HttpClient> {
+    inner: T,
+    config: CacheConfig,
+    state: Mutex<CacheState>,
+}
+
+impl<T: HttpClientTrait> CachedHttpClient<T> {
+    pub fn with_config(inner: T, config: CacheConfig) -> Self {
+        CachedHttpClient {
+            inner,
+            state: Mutex::new(CacheState {
+                entries: LruCache::new(config.capacity),
+                stats: CacheStats::default(),
+            }),
+            config,
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let state = self.state.lock().unwrap();
+        CacheStats {
+            entries: state.entries.len(),
+            ..state.stats
+        }
+    }
+
+    /// Drops every response, statistics are kept
+    pub fn clear(&self) {
+        self.state.lock().unwrap().entries.clear();
+    }
+
+    fn lookup(&self, key: &str) -> Option<HttpResponse> {
+        let mut state = self.state.lock().unwrap();
+        let response = match state.entries.get(key) {
+            Some(entry) if entry.expires_at > Instant::now() => Some(entry.response.clone()),
+            Some(_) => {
+                state.entries.pop(key);
+                None
+            }
+            None => None,
+        };
+
+        match response {
+            Some(_) => state.stats.hits += 1,
+            None => state.stats.misses += 1,
+        }
+        response
+    }
+
+    fn store(&self, key: String, response: &HttpResponse, ttl: Duration) {
+        let mut state = self.state.lock().unwrap();
+        let entry = CacheEntry {
+            response: response.clone(),
+            expires_at: Instant::now() + ttl,
+        };
+
+        if let Some((evicted, _)) = state.entries.push(key.clone(), entry) {
+            if evicted != key {
+                state.stats.evictions += 1;
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<T: HttpClientTrait> HttpClientTrait for CachedHttpClient<T> {
+    fn new() -> Self {
+        CachedHttpClient::with_config(T::new(), CacheConfig::default())
+    }
+
+    async fn get(&self, url: &str) -> Result<HttpResponse, Error> {
+        let Ok(parsed) = Url::parse(url) else {
+            return self.inner.get(url).await;
+        };
+        let ttl = self.config.ttl(&parsed);
+        if ttl.is_zero() {
+            return self.inner.get(url).await;
+        }
+
+        let key = normalize_url(parsed);
+        if let Some(response) = self.lookup(&key) {
+            return Ok(response);
+        }
+
+        let response = self.inner.get(url).await?;
+        if response.is_success() && !response.is_error_envelope() {
+            self.store(key, &response, ttl);
+        }
+        Ok(response)
+    }
+
+    // Conditional requests are answered by the server, they bypass the cache
+    async fn get_with_headers(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> Result<HttpResponse, Error> {
+        if headers.is_empty() {
+            return self.get(url).await;
+        }
+        self.inner.get_with_headers(url, headers).await
+    }
+}
+
+/// Removes the API key from an url, so it is never shown in errors nor written to disk
+pub(crate) fn without_api_key(mut url: Url) -> Url {
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(name, _)| name != "key")
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect();
+
+    if pairs.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+
+    url
+}
+
+/// Sorts the query parameters so the same request always has the same key
+pub(crate) fn normalize_url(mut url: Url) -> String {
+    let mut pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect();
+    pairs.sort();
+
+    url.set_fragment(None);
+    if pairs.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+
+    url.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{MockHttpClient, MockResponse};
+    use crate::youtube::video::Video;
+    use std::sync::Arc;
+
+    const VIDEO_URL: &str = "https://yt.lemnoslife.com/noKey/videos?part=snippet&id=dQw4w9WgXcQ";
+
+    #[test]
+    fn urls_are_normalized() {
+        let url =
+            Url::parse("https://YT.lemnoslife.com/noKey/videos?id=a&part=snippet#top").unwrap();
+
+        assert_eq!(
+            normalize_url(url),
+            "https://yt.lemnoslife.com/noKey/videos?id=a&part=snippet"
+        );
+    }
+
+    #[tokio::test]
+    async fn repeated_requests_are_served_from_the_cache() {
+        let client = CachedHttpClient::with_config(
+            MockHttpClient::new(vec![
+                MockResponse::new(200, "second"),
+                MockResponse::new(200, "first"),
+            ]),
+            CacheConfig::default(),
+        );
+
+        let first = client.get(VIDEO_URL).await.unwrap();
+        let second = client
+            .get("https://yt.lemnoslife.com/noKey/videos?id=dQw4w9WgXcQ&part=snippet")
+            .await
+            .unwrap();
+
+        assert_eq!(first.body, "first");
+        assert_eq!(second.body, "first");
+        assert_eq!(client.inner.requested_urls().len(), 1);
+        assert_eq!(
+            client.stats(),
+            CacheStats {
+                hits: 1,
+                misses: 1,
+                evictions: 0,
+                entries: 1
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn successful_error_envelopes_are_not_cached() {
+        let client = CachedHttpClient::with_config(
+            MockHttpClient::new(vec![
+                MockResponse::new(200, r#"{"items": []}"#),
+                MockResponse::new(
+                    200,
+                    r#"{"error": {"code": 403, "message": "quotaExceeded"}}"#,
+                ),
+            ]),
+            CacheConfig::default(),
+        );
+
+        client.get(VIDEO_URL).await.unwrap();
+        assert_eq!(
+            client.get(VIDEO_URL).await.unwrap().body,
+            r#"{"items": []}"#
+        );
+        assert_eq!(client.inner.requested_urls().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn expired_and_failed_responses_are_requested_again() {
+        let client = CachedHttpClient::with_config(
+            MockHttpClient::new(vec![
+                MockResponse::new(200, "third"),
+                MockResponse::new(200, "second"),
+                MockResponse::new(503, "first"),
+            ]),
+            CacheConfig::default().with_ttl("videos", Duration::from_millis(20)),
+        );
+
+        assert_eq!(client.get(VIDEO_URL).await.unwrap().status, 503);
+        assert_eq!(client.get(VIDEO_URL).await.unwrap().body, "second");
+        crate::utils::runtime::sleep(Duration::from_millis(30)).await;
+        assert_eq!(client.get(VIDEO_URL).await.unwrap().body, "third");
+        assert_eq!(client.stats().misses, 3);
+    }
+
+    #[tokio::test]
+    async fn least_recently_used_responses_are_evicted() {
+        let client = CachedHttpClient::with_config(
+            MockHttpClient::new(vec![
+                MockResponse::new(200, "c"),
+                MockResponse::new(200, "b"),
+                MockResponse::new(200, "a"),
+            ]),
+            CacheConfig::default().with_capacity(NonZeroUsize::new(2).unwrap()),
+        );
+
+        client.get("https://example.com/videos?id=a").await.unwrap();
+        client.get("https://example.com/videos?id=b").await.unwrap();
+        client.get("https://example.com/videos?id=a").await.unwrap();
+        client.get("https://example.com/videos?id=c").await.unwrap();
+
+        let stats = client.stats();
+        assert_eq!(stats.evictions, 1);
+        assert_eq!(stats.entries, 2);
+        assert_eq!(client.lookup("https://example.com/videos?id=b"), None);
+        assert_eq!(
+            client
+                .lookup("https://example.com/videos?id=a")
+                .unwrap()
+                .body,
+            "a"
+        );
+    }
+
+    #[tokio::test]
+    async fn repeated_video_searches_are_served_from_the_cache() {
+        let response = r#"{"items": [{"snippet": {
+            "publishedAt": "2009-10-25T06:57:33Z",
+            "title": "Video Title",
+            "description": "Video Description"
+        }}]}"#;
+        let client = Arc::new(CachedHttpClient::with_config(
+            MockHttpClient::new(vec![MockResponse::new(200, response)]),
+            CacheConfig::default(),
+        ));
+
+        for _ in 0..3 {
+            let video = Video::search_video_by_id("dQw4w9WgXcQ".parse().unwrap(), client.clone())
+                .await
+                .unwrap();
+            assert_eq!(video.title.as_deref(), Some("Video Title"));
+        }
+
+        assert_eq!(client.stats().hits, 2);
+        assert_eq!(client.inner.requested_urls().len(), 1);
+    }
+}
@@ -0,0 +1,134 @@
+use async_trait::async_trait;
+use futures::future::{BoxFuture, FutureExt, Shared};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use url::Url;
+
+use super::cache::normalize_url;
+use super::http_client::{HttpClient, HttpClientTrait, HttpResponse};
+use crate::error::Error;
+
+type InFlight = Shared<BoxFuture<'static, Result<HttpResponse, Error>>>;
+
+/// Sends a single request when the same url is requested several times concurrently, every
+/// caller receives a clone of its response or error
+pub struct CoalescingHttpClient<T: HttpClientTrait = HttpClient> {
+    inner: Arc<T>,
+    in_flight: Arc<Mutex<HashMap<String, InFlight>>>,
+}
+
+impl<T: HttpClientTrait + 'static> CoalescingHttpClient<T> {
+    pub fn with_client(inner: T) -> Self {
+        CoalescingHttpClient {
+            inner: Arc::new(inner),
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Number of requests currently being sent
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.lock().unwrap().len()
+    }
+
+    fn request(&self, url: &str) -> InFlight {
+        let key = Url::parse(url)
+            .map(normalize_url)
+            .unwrap_or_else(|_| url.to_owned());
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if let Some(request) = in_flight.get(&key) {
+            return request.clone();
+        }
+
+        let inner = self.inner.clone();
+        let requests = self.in_flight.clone();
+        let url = url.to_owned();
+        let finished = key.clone();
+        let request = async move {
+            let response = inner.get(&url).await;
+            // Removed by the request itself, so it is cleaned up even when the caller that
+            // started it was dropped and another one finished it
+            requests.lock().unwrap().remove(&finished);
+            response
+        }
+        .boxed()
+        .shared();
+
+        in_flight.insert(key, request.clone());
+        request
+    }
+}
+
+#[async_trait]
+impl<T: HttpClientTrait + 'static> HttpClientTrait for CoalescingHttpClient<T> {
+    fn new() -> Self {
+        CoalescingHttpClient::with_client(T::new())
+    }
+
+    async fn get(&self, url: &str) -> Result<HttpResponse, Error> {
+        self.request(url).await
+    }
+
+    // Conditional requests depend on their headers, they are never shared
+    async fn get_with_headers(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> Result<HttpResponse, Error> {
+        if headers.is_empty() {
+            return self.get(url).await;
+        }
+        self.inner.get_with_headers(url, headers).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{MockHttpClient, MockResponse};
+    use crate::youtube::channel::Channel;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn concurrent_identical_requests_are_sent_once() {
+        let client = Arc::new(CoalescingHttpClient::with_client(MockHttpClient::new(
+            vec![
+                MockResponse::new(200, "second"),
+                MockResponse::new(200, "first").with_delay(Duration::from_millis(50)),
+            ],
+        )));
+
+        let responses = futures::future::join_all((0..10).map(|_| {
+            client.get("https://yt.lemnoslife.com/noKey/search?q=rickastleyyt&part=snippet")
+        }))
+        .await;
+
+        assert!(responses
+            .iter()
+            .all(|response| response.as_ref().unwrap().body == "first"));
+        assert_eq!(client.inner.requested_urls().len(), 1);
+        assert_eq!(client.in_flight(), 0);
+
+        let later = client
+            .get("https://yt.lemnoslife.com/noKey/search?part=snippet&q=rickastleyyt")
+            .await
+            .unwrap();
+        assert_eq!(later.body, "second");
+    }
+
+    #[tokio::test]
+    async fn errors_are_shared_with_every_caller() {
+        let client = Arc::new(CoalescingHttpClient::with_client(MockHttpClient::new(
+            vec![MockResponse::new(503, "").with_delay(Duration::from_millis(50))],
+        )));
+
+        let channels = futures::future::join_all(
+            (0..5).map(|_| Channel::initialize("rickastleyyt".parse().unwrap(), client.clone())),
+        )
+        .await;
+
+        assert!(channels
+            .iter()
+            .all(|channel| matches!(channel, Err(Error::Unavailable))));
+        assert_eq!(client.inner.requested_urls().len(), 1);
+    }
+}
@@ -0,0 +1,597 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use url::Url;
+
+use super::cache::{normalize_url, without_api_key};
+use super::http_client::{HttpClient, HttpClientTrait, HttpResponse};
+use crate::error::Error;
+
+/// A response kept by a `CacheStore`, along with what is needed to revalidate it. Unlike the
+/// models, it is serializable without the `serde` feature since every store writes it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredResponse {
+    /// Normalized url of the request, without its API key
+    pub key: String,
+    pub response: HttpResponse,
+    pub stored_at: SystemTime,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    /// How long the response is used without asking the server again
+    pub max_age: Duration,
+}
+
+impl StoredResponse {
+    fn is_fresh(&self) -> bool {
+        self.stored_at.elapsed().is_ok_and(|age| age < self.max_age)
+    }
+
+    /// Headers that let the server answer `304 Not Modified` when the response did not change
+    fn validators(&self) -> Vec<(&str, &str)> {
+        let etag = self.etag.as_deref().map(|etag| ("If-None-Match", etag));
+        let last_modified = self
+            .last_modified
+            .as_deref()
+            .map(|date| ("If-Modified-Since", date));
+
+        etag.into_iter().chain(last_modified).collect()
+    }
+}
+
+/// Which responses `CacheStore::prune` removes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PruneOptions {
+    /// Removes the responses stored longer ago than this
+    pub max_age: Option<Duration>,
+    /// Removes the oldest responses until the store takes at most this many bytes
+    pub max_size: Option<u64>,
+}
+
+/// Where a `PersistentHttpClient` keeps its responses between restarts. The clients call it
+/// directly from their async `get`, so a store that blocks, like `FileCacheStore`, blocks the
+/// executor for as long as each call takes
+pub trait CacheStore: Send + Sync {
+    fn load(&self, key: &str) -> io::Result<Option<StoredResponse>>;
+    fn save(&self, response: &StoredResponse) -> io::Result<()>;
+    fn remove(&self, key: &str) -> io::Result<()>;
+    /// Returns the number of responses removed
+    fn prune(&self, options: &PruneOptions) -> io::Result<usize>;
+}
+
+/// Keeps every response as a json file of a directory, the directory is created on the
+/// first write. Files are read and written with blocking `std::fs` calls, one small file per
+/// request, and `prune` reads every file of the directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileCacheStore {
+    directory: PathBuf,
+}
+
+impl Default for FileCacheStore {
+    fn default() -> Self {
+        FileCacheStore::new(std::env::temp_dir().join("youtube_search"))
+    }
+}
+
+impl FileCacheStore {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        FileCacheStore {
+            directory: directory.into(),
+        }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        // FNV-1a, file names have to stay the same across builds and Rust versions
+        let hash = key.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+        self.directory.join(format!("{:016x}.json", hash))
+    }
+
+    /// Every stored file with its size and when its response was stored
+    fn files(&self) -> io::Result<Vec<(PathBuf, u64, SystemTime)>> {
+        let entries = match fs::read_dir(&self.directory) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            entries => entries?,
+        };
+
+        let mut files = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+            {
+                let content = fs::read(&path)?;
+                // Ages come from the responses rather than the files, which copying or
+                // touching the directory changes. Unreadable files are pruned first
+                let stored_at = serde_json::from_slice::<StoredResponse>(&content)
+                    .map_or(SystemTime::UNIX_EPOCH, |stored| stored.stored_at);
+                files.push((path, content.len() as u64, stored_at));
+            }
+        }
+        Ok(files)
+    }
+}
+
+impl CacheStore for FileCacheStore {
+    fn load(&self, key: &str) -> io::Result<Option<StoredResponse>> {
+        let content = match fs::read(self.path(key)) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            content => content?,
+        };
+        let stored: StoredResponse = serde_json::from_slice(&content)?;
+
+        // Two urls may share the same file name
+        Ok(Some(stored).filter(|stored| stored.key == key))
+    }
+
+    fn save(&self, response: &StoredResponse) -> io::Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        let path = self.path(&response.key);
+
+        // Written aside first so a crash never leaves a truncated response behind, under a
+        // unique name so concurrent saves of the same response do not mix their content
+        let mut temporary = tempfile::NamedTempFile::new_in(&self.directory)?;
+        temporary.write_all(&serde_json::to_vec(response)?)?;
+        temporary.persist(path)?;
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> io::Result<()> {
+        match fs::remove_file(self.path(key)) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+
+    fn prune(&self, options: &PruneOptions) -> io::Result<usize> {
+        let mut files = self.files()?;
+        let mut removed = 0;
+
+        if let Some(max_age) = options.max_age {
+            let mut kept = Vec::new();
+            for (path, size, stored_at) in files {
+                if stored_at.elapsed().is_ok_and(|age| age > max_age) {
+                    fs::remove_file(path)?;
+                    removed += 1;
+                } else {
+                    kept.push((path, size, stored_at));
+                }
+            }
+            files = kept;
+        }
+
+        if let Some(max_size) = options.max_size {
+            files.sort_by_key(|(_, _, stored_at)| *stored_at);
+            let mut total: u64 = files.iter().map(|(_, size, _)| size).sum();
+            for (path, size, _) in files {
+                if total <= max_size {
+                    break;
+                }
+                fs::remove_file(path)?;
+                total -= size;
+                removed += 1;
+            }
+        }
+
+        Ok(removed)
+    }
+}
+
+/// Directives of a `Cache-Control` header this crate acts on
+#[derive(Debug, Default, PartialEq, Eq)]
+struct CacheControl {
+    no_store: bool,
+    no_cache: bool,
+    max_age: Option<Duration>,
+}
+
+impl CacheControl {
+    fn parse(value: Option<&str>) -> Self {
+        let mut cache_control = CacheControl::default();
+        for directive in value.unwrap_or_default().split(',').map(str::trim) {
+            let (name, argument) = directive.split_once('=').unwrap_or((directive, ""));
+            match name.to_ascii_lowercase().as_str() {
+                "no-store" => cache_control.no_store = true,
+                "no-cache" => cache_control.no_cache = true,
+                "max-age" => {
+                    cache_control.max_age = argument
+                        .trim_matches('"')
+                        .parse()
+                        .ok()
+                        .map(Duration::from_secs)
+                }
+                _ => {}
+            }
+        }
+        cache_control
+    }
+}
+
+/// Key of a request in a `CacheStore`, the API key is left out so it is never written to disk
+fn store_key(url: Url) -> String {
+    normalize_url(without_api_key(url))
+}
+
+/// Keeps responses in a `CacheStore` so they survive restarts. Responses are used as long as
+/// their `Cache-Control: max-age` allows, then revalidated with their `ETag` and
+/// `Last-Modified` headers so unchanged ones are not downloaded again. The store is called
+/// without leaving the executor, see `CacheStore`
+pub struct PersistentHttpClient<S: CacheStore = FileCacheStore, T: HttpClientTrait = HttpClient> {
+    inner: T,
+    store: S,
+    default_max_age: Duration,
+}
+
+impl<S: CacheStore, T: HttpClientTrait> PersistentHttpClient<S, T> {
+    pub fn with_store(inner: T, store: S) -> Self {
+        PersistentHttpClient {
+            inner,
+            store,
+            default_max_age: Duration::ZERO,
+        }
+    }
+
+    /// How long responses without `Cache-Control: max-age` are used before revalidating them,
+    /// they are revalidated on every request by default
+    pub fn with_default_max_age(mut self, max_age: Duration) -> Self {
+        self.default_max_age = max_age;
+        self
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Returns the number of responses removed
+    pub fn prune(&self, options: &PruneOptions) -> io::Result<usize> {
+        self.store.prune(options)
+    }
+
+    fn save(&self, key: String, response: &HttpResponse) {
+        if !response.is_success() || response.is_error_envelope() {
+            return;
+        }
+        let cache_control = CacheControl::parse(response.header("cache-control"));
+        if cache_control.no_store {
+            return;
+        }
+
+        let max_age = match cache_control {
+            CacheControl { no_cache: true, .. } => Duration::ZERO,
+            CacheControl {
+                max_age: Some(max_age),
+                ..
+            } => max_age,
+            _ => self.default_max_age,
+        };
+        let stored = StoredResponse {
+            key,
+            response: HttpResponse {
+                url: Url::parse(&response.url)
+                    .map(|url| without_api_key(url).to_string())
+                    .unwrap_or_else(|_| response.url.clone()),
+                ..response.clone()
+            },
+            stored_at: SystemTime::now(),
+            etag: response.header("etag").map(str::to_owned),
+            last_modified: response.header("last-modified").map(str::to_owned),
+            max_age,
+        };
+
+        // A cache that cannot be written must not fail the request
+        let _ = self.store.save(&stored);
+    }
+
+    fn refresh(&self, stored: StoredResponse, not_modified: &HttpResponse) -> HttpResponse {
+        let cache_control = CacheControl::parse(not_modified.header("cache-control"));
+        let stored = StoredResponse {
+            stored_at: SystemTime::now(),
+            max_age: cache_control.max_age.unwrap_or(stored.max_age),
+            ..stored
+        };
+
+        let _ = self.store.save(&stored);
+        stored.response
+    }
+}
+
+#[async_trait]
+impl<S: CacheStore + Default, T: HttpClientTrait> HttpClientTrait for PersistentHttpClient<S, T> {
+    fn new() -> Self {
+        PersistentHttpClient::with_store(T::new(), S::default())
+    }
+
+    async fn get(&self, url: &str) -> Result<HttpResponse, Error> {
+        let Ok(parsed) = Url::parse(url) else {
+            return self.inner.get(url).await;
+        };
+        let key = store_key(parsed);
+
+        // An unreadable response is requested again and overwritten
+        let stored = self.store.load(&key).ok().flatten();
+        let response = match stored {
+            Some(stored) if stored.is_fresh() => return Ok(stored.response),
+            Some(stored) if !stored.validators().is_empty() => {
+                let response = self
+                    .inner
+                    .get_with_headers(url, &stored.validators())
+                    .await?;
+                if response.status == 304 {
+                    return Ok(self.refresh(stored, &response));
+                }
+                response
+            }
+            _ => self.inner.get(url).await?,
+        };
+
+        self.save(key, &response);
+        Ok(response)
+    }
+}
+
+/// Never touches the network, answers only with the responses of a `CacheStore`, however old
+/// they are, and fails with `Error::NotCached` for every other request
+pub struct OfflineHttpClient<S: CacheStore = FileCacheStore> {
+    store: S,
+}
+
+impl<S: CacheStore> OfflineHttpClient<S> {
+    pub fn with_store(store: S) -> Self {
+        OfflineHttpClient { store }
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+}
+
+#[async_trait]
+impl<S: CacheStore + Default> HttpClientTrait for OfflineHttpClient<S> {
+    fn new() -> Self {
+        OfflineHttpClient::with_store(S::default())
+    }
+
+    async fn get(&self, url: &str) -> Result<HttpResponse, Error> {
+        let not_cached = || Error::NotCached {
+            url: url.to_owned(),
+        };
+        let key = Url::parse(url).map(store_key).map_err(|_| not_cached())?;
+
+        match self.store.load(&key) {
+            Ok(Some(stored)) => Ok(stored.response),
+            _ => Err(not_cached()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{MockHttpClient, MockResponse};
+
+    const VIDEO_URL: &str = "https://yt.lemnoslife.com/noKey/videos?part=snippet&id=dQw4w9WgXcQ";
+
+    fn client_with_responses(
+        store: &FileCacheStore,
+        responses: Vec<MockResponse>,
+    ) -> PersistentHttpClient<FileCacheStore, MockHttpClient> {
+        PersistentHttpClient::with_store(MockHttpClient::new(responses), store.clone())
+    }
+
+    #[test]
+    fn cache_control_is_parsed() {
+        assert_eq!(
+            CacheControl::parse(Some("private, max-age=300, must-revalidate")),
+            CacheControl {
+                max_age: Some(Duration::from_secs(300)),
+                ..Default::default()
+            }
+        );
+        assert!(CacheControl::parse(Some("No-Store")).no_store);
+        assert_eq!(CacheControl::parse(None), CacheControl::default());
+    }
+
+    #[tokio::test]
+    async fn stored_responses_are_revalidated_after_a_restart() {
+        let directory = tempfile::tempdir().unwrap();
+        let store = FileCacheStore::new(directory.path());
+
+        let client = client_with_responses(
+            &store,
+            vec![MockResponse::new(200, "video").with_header("ETag", "\"abc\"")],
+        );
+        assert_eq!(client.get(VIDEO_URL).await.unwrap().body, "video");
+
+        let restarted = client_with_responses(&store, vec![MockResponse::new(304, "")]);
+        let response = restarted.get(VIDEO_URL).await.unwrap();
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, "video");
+        assert_eq!(
+            restarted.inner.requested_headers(),
+            vec![vec![("If-None-Match".to_owned(), "\"abc\"".to_owned())]]
+        );
+    }
+
+    #[tokio::test]
+    async fn changed_responses_replace_stored_ones() {
+        let directory = tempfile::tempdir().unwrap();
+        let store = FileCacheStore::new(directory.path());
+        let client = client_with_responses(
+            &store,
+            vec![
+                MockResponse::new(200, "new"),
+                MockResponse::new(200, "old")
+                    .with_header("Last-Modified", "Wed, 21 Oct 2015 07:28:00 GMT"),
+            ],
+        );
+
+        client.get(VIDEO_URL).await.unwrap();
+        assert_eq!(client.get(VIDEO_URL).await.unwrap().body, "new");
+
+        assert_eq!(
+            client.inner.requested_headers()[1],
+            vec![(
+                "If-Modified-Since".to_owned(),
+                "Wed, 21 Oct 2015 07:28:00 GMT".to_owned()
+            )]
+        );
+        let stored = store
+            .load(&store_key(Url::parse(VIDEO_URL).unwrap()))
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.response.body, "new");
+        assert_eq!(stored.last_modified, None);
+    }
+
+    #[tokio::test]
+    async fn cache_control_is_honored() {
+        let directory = tempfile::tempdir().unwrap();
+        let store = FileCacheStore::new(directory.path());
+        let client = client_with_responses(
+            &store,
+            vec![
+                MockResponse::new(200, "private").with_header("Cache-Control", "no-store"),
+                MockResponse::new(200, "fresh").with_header("Cache-Control", "max-age=3600"),
+            ],
+        );
+
+        assert_eq!(client.get(VIDEO_URL).await.unwrap().body, "fresh");
+        assert_eq!(client.get(VIDEO_URL).await.unwrap().body, "fresh");
+        assert_eq!(client.inner.requested_urls().len(), 1);
+
+        let other_url = "https://yt.lemnoslife.com/noKey/channels?id=UCuAXFkgsw1L7xaCfnd5JJOw";
+        client.get(other_url).await.unwrap();
+        assert_eq!(
+            store
+                .load(&store_key(Url::parse(other_url).unwrap()))
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn offline_clients_only_answer_with_stored_responses() {
+        let directory = tempfile::tempdir().unwrap();
+        let store = FileCacheStore::new(directory.path());
+        let client = client_with_responses(
+            &store,
+            vec![MockResponse::new(200, "video").with_header("Cache-Control", "max-age=0")],
+        );
+        client.get(VIDEO_URL).await.unwrap();
+
+        let offline = OfflineHttpClient::with_store(store);
+        let other_url = "https://yt.lemnoslife.com/noKey/videos?part=snippet&id=9bZkp7q19f0";
+
+        assert_eq!(offline.get(VIDEO_URL).await.unwrap().body, "video");
+        match offline.get(other_url).await {
+            Err(Error::NotCached { url }) => assert_eq!(url, other_url),
+            _ => panic!("Expected a not cached error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn api_keys_are_not_written_to_disk() {
+        let directory = tempfile::tempdir().unwrap();
+        let store = FileCacheStore::new(directory.path());
+        let client = client_with_responses(&store, vec![MockResponse::new(200, "video")]);
+        let url = "https://www.googleapis.com/youtube/v3/videos?part=snippet&key=secret";
+
+        client.get(url).await.unwrap();
+
+        let stored = store
+            .load("https://www.googleapis.com/youtube/v3/videos?part=snippet")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            stored.response.url,
+            "https://www.googleapis.com/youtube/v3/videos?part=snippet"
+        );
+        for (path, _, _) in store.files().unwrap() {
+            assert!(!fs::read_to_string(path).unwrap().contains("secret"));
+        }
+    }
+
+    #[test]
+    fn stores_are_pruned_by_age_and_size() {
+        let directory = tempfile::tempdir().unwrap();
+        let store = FileCacheStore::new(directory.path());
+        for id in ["a", "b", "c"] {
+            store
+                .save(&StoredResponse {
+                    key: format!("https://example.com/videos?id={}", id),
+                    response: HttpResponse::new("https://example.com/videos", 200, "body"),
+                    stored_at: SystemTime::now(),
+                    etag: None,
+                    last_modified: None,
+                    max_age: Duration::ZERO,
+                })
+                .unwrap();
+        }
+        let size = fs::metadata(store.path("https://example.com/videos?id=a"))
+            .unwrap()
+            .len();
+
+        let removed = store
+            .prune(&PruneOptions {
+                max_age: Some(Duration::from_secs(3600)),
+                max_size: Some(size * 2),
+            })
+            .unwrap();
+
+        assert_eq!(removed, 1);
+        assert_eq!(store.files().unwrap().len(), 2);
+        assert_eq!(
+            store
+                .prune(&PruneOptions {
+                    max_age: Some(Duration::ZERO),
+                    max_size: None,
+                })
+                .unwrap(),
+            2
+        );
+    }
+
+    #[test]
+    fn stores_are_pruned_by_the_time_responses_were_stored() {
+        let directory = tempfile::tempdir().unwrap();
+        let store = FileCacheStore::new(directory.path());
+        let hour = Duration::from_secs(3600);
+        for (id, age) in [
+            ("old", 2 * hour),
+            ("older", 3 * hour),
+            ("new", Duration::ZERO),
+        ] {
+            store
+                .save(&StoredResponse {
+                    key: format!("https://example.com/videos?id={}", id),
+                    response: HttpResponse::new("https://example.com/videos", 200, "body"),
+                    stored_at: SystemTime::now() - age,
+                    etag: None,
+                    last_modified: None,
+                    max_age: Duration::ZERO,
+                })
+                .unwrap();
+        }
+        let size = fs::metadata(store.path("https://example.com/videos?id=new"))
+            .unwrap()
+            .len();
+
+        let removed = store
+            .prune(&PruneOptions {
+                max_age: Some(Duration::from_secs(150 * 60)),
+                max_size: Some(size + 1),
+            })
+            .unwrap();
+
+        assert_eq!(removed, 2);
+        assert!(store
+            .load("https://example.com/videos?id=new")
+            .unwrap()
+            .is_some());
+    }
+}
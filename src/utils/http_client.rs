@@ -1,14 +1,79 @@
 use async_trait::async_trait;
-use reqwest::{Client, Error, Response};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::error::Error;
+
+/// A response whose body has already been read, so it can be cloned and cached. It is
+/// serializable without the `serde` feature so a `CacheStore` can write it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpResponse {
+    pub url: String,
+    pub status: u16,
+    /// Header names are lowercase
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn new(url: &str, status: u16, body: &str) -> Self {
+        HttpResponse {
+            url: url.to_owned(),
+            status,
+            headers: Vec::new(),
+            body: body.to_owned(),
+        }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers
+            .push((name.to_ascii_lowercase(), value.to_owned()));
+        self
+    }
+
+    /// Value of the first header with this name, names are case insensitive
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Whether the body is the `{"error": ...}` envelope of the API, which it may send even
+    /// with a successful status
+    pub(crate) fn is_error_envelope(&self) -> bool {
+        // Most bodies are not errors, they are not parsed
+        self.body.contains("\"error\"")
+            && serde_json::from_str::<serde_json::Value>(&self.body)
+                .is_ok_and(|body| body.get("error").is_some_and(|error| error.is_object()))
+    }
+}
 
 pub struct HttpClient {
     client: Client,
 }
 
+/// Sends the requests of this crate, implement it to add caching, retries or logging
+/// around another client
 #[async_trait]
-pub trait HttpClientTrait {
+pub trait HttpClientTrait: Send + Sync {
     fn new() -> Self;
-    async fn get(&self, url: &str) -> Result<Response, Error>;
+    async fn get(&self, url: &str) -> Result<HttpResponse, Error>;
+
+    /// Sends a request with extra headers, e.g. `If-None-Match`, clients that cannot send
+    /// headers ignore them
+    async fn get_with_headers(
+        &self,
+        url: &str,
+        _headers: &[(&str, &str)],
+    ) -> Result<HttpResponse, Error> {
+        self.get(url).await
+    }
 }
 
 #[async_trait]
@@ -19,7 +84,34 @@ impl HttpClientTrait for HttpClient {
         }
     }
 
-    async fn get(&self, url: &str) -> Result<Response, Error> {
-        self.client.get(url).send().await
+    async fn get(&self, url: &str) -> Result<HttpResponse, Error> {
+        self.get_with_headers(url, &[]).await
+    }
+
+    async fn get_with_headers(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> Result<HttpResponse, Error> {
+        let network_error = |e| Error::Network(Arc::new(e));
+        let request = headers
+            .iter()
+            .fold(self.client.get(url), |request, (name, value)| {
+                request.header(*name, *value)
+            });
+        let response = request.send().await.map_err(network_error)?;
+
+        Ok(HttpResponse {
+            url: response.url().to_string(),
+            status: response.status().as_u16(),
+            headers: response
+                .headers()
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.as_str().to_owned(), value.to_str().ok()?.to_owned()))
+                })
+                .collect(),
+            body: response.text().await.map_err(network_error)?,
+        })
     }
 }
@@ -1 +1,6 @@
+pub mod cache;
+pub mod coalesce;
+pub mod disk_cache;
 pub mod http_client;
+pub(crate) mod runtime;
+pub(crate) mod timestamp;
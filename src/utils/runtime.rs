@@ -0,0 +1,10 @@
+use std::time::Duration;
+
+/// Waits without blocking the executor, on the tokio timer when the `tokio` feature is
+/// enabled and on a `futures-timer` background thread otherwise, so any executor can poll it
+pub(crate) async fn sleep(duration: Duration) {
+    #[cfg(feature = "tokio")]
+    tokio::time::sleep(duration).await;
+    #[cfg(not(feature = "tokio"))]
+    futures_timer::Delay::new(duration).await;
+}
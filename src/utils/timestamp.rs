@@ -0,0 +1,94 @@
+//! RFC 3339 times, as returned by the API, e.g. `2023-01-01T10:00:00Z`
+
+use std::cmp::Ordering;
+use time::format_description::well_known::Rfc3339;
+use time::{OffsetDateTime, UtcOffset};
+
+/// Instant of an RFC 3339 time with any offset and precision, e.g.
+/// `2023-01-01T12:00:00.25+02:00`
+pub(crate) fn parse(value: &str) -> Option<OffsetDateTime> {
+    OffsetDateTime::parse(value, &Rfc3339).ok()
+}
+
+/// RFC 3339 time in UTC, in the format of the API, with the fraction of a second if any
+pub(crate) fn format(instant: OffsetDateTime) -> Option<String> {
+    instant.to_offset(UtcOffset::UTC).format(&Rfc3339).ok()
+}
+
+/// Orders two RFC 3339 times as instants, whatever their offset and precision. Times that
+/// cannot be parsed are compared as strings, which is right for the UTC times of the API
+pub(crate) fn compare(a: &str, b: &str) -> Ordering {
+    match (parse(a), parse(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalized(value: &str) -> Option<String> {
+        parse(value).and_then(format)
+    }
+
+    #[test]
+    fn times_are_normalized_to_utc() {
+        assert_eq!(
+            normalized("2023-01-01T12:00:00+02:00").as_deref(),
+            Some("2023-01-01T10:00:00Z")
+        );
+        assert_eq!(
+            normalized("2022-12-31T23:30:00-10:30").as_deref(),
+            Some("2023-01-01T10:00:00Z")
+        );
+        assert_eq!(
+            normalized("2023-01-01T09:59:59.250Z").as_deref(),
+            Some("2023-01-01T09:59:59.25Z")
+        );
+        assert_eq!(
+            normalized("2023-01-01T10:00:00.000Z").as_deref(),
+            Some("2023-01-01T10:00:00Z")
+        );
+    }
+
+    #[test]
+    fn invalid_times_are_rejected() {
+        for value in [
+            "",
+            "2023-01-01",
+            "2023-02-30T10:00:00Z",
+            "2023-01-01T24:00:00Z",
+            "2023-01-01T10:00:00",
+            "2023-01-01T10:00:00.Z",
+            "2023-01-01T10:00:00+2:00",
+            "2023-1-01T10:00:00Z",
+        ] {
+            assert_eq!(parse(value), None, "{}", value);
+        }
+    }
+
+    #[test]
+    fn times_are_compared_as_instants() {
+        assert_eq!(
+            compare("2023-01-01T12:00:00+02:00", "2023-01-01T10:00:00Z"),
+            Ordering::Equal
+        );
+        assert_eq!(
+            compare("2023-01-01T11:00:00+02:00", "2023-01-01T10:00:00Z"),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare("2023-01-01T09:59:59.250Z", "2023-01-01T10:00:00Z"),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare("2023-01-01T10:00:00.25Z", "2023-01-01T10:00:00.5Z"),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare("2023-01-01T10:00:00.5Z", "2023-01-01T10:00:00.500Z"),
+            Ordering::Equal
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use futures::stream::{self, StreamExt, TryStreamExt};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::hash::Hash;
+
+use crate::error::Error;
+
+/// Maximum number of ids the API accepts in a single request
+pub(crate) const BATCH_SIZE: usize = 50;
+/// Maximum number of requests of a batch sent at the same time
+pub(crate) const BATCH_CONCURRENCY: usize = 4;
+
+/// Result of looking up many ids at once
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BatchLookup<I, T> {
+    /// Resources that were found, in the order of the ids they were looked up with
+    pub found: Vec<T>,
+    /// Ids that do not match any resource, in the order they were given
+    pub not_found: Vec<I>,
+    /// Items of the responses that could not be decoded, their ids are neither found nor
+    /// not found
+    pub skipped: Vec<SkippedItem<I>>,
+}
+
+/// An item of a batch response that could not be decoded
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SkippedItem<I> {
+    /// Id of the item, `None` when the item has no id
+    pub id: Option<I>,
+    /// Path to the field that failed to decode, e.g. `items[0].snippet.title`
+    pub path: String,
+    pub message: String,
+}
+
+/// Looks up ids by chunks of `BATCH_SIZE`, the chunks are fetched concurrently. Each chunk
+/// returns the items it decoded along with the ones it skipped
+pub(crate) async fn lookup<I, T, F, Fut>(
+    ids: &[I],
+    fetch: F,
+    id_of: impl Fn(&T) -> &I,
+) -> Result<BatchLookup<I, T>, Error>
+where
+    I: Clone + Eq + Hash,
+    T: Clone,
+    F: Fn(Vec<I>) -> Fut,
+    Fut: Future<Output = Result<(Vec<T>, Vec<SkippedItem<I>>), Error>>,
+{
+    let mut seen = HashSet::new();
+    let unique: Vec<I> = ids.iter().filter(|id| seen.insert(*id)).cloned().collect();
+
+    // Chunks are kept in order so skipped items are reported in the order of their ids
+    let chunks: Vec<(Vec<T>, Vec<SkippedItem<I>>)> =
+        stream::iter(unique.chunks(BATCH_SIZE).map(<[I]>::to_vec))
+            .map(fetch)
+            .buffered(BATCH_CONCURRENCY)
+            .try_collect()
+            .await?;
+
+    let mut found = HashMap::new();
+    let mut batch = BatchLookup {
+        found: Vec::new(),
+        not_found: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for (items, skipped) in chunks {
+        found.extend(items.into_iter().map(|item| (id_of(&item).clone(), item)));
+        batch.skipped.extend(skipped);
+    }
+
+    let skipped: HashSet<&I> = batch
+        .skipped
+        .iter()
+        .filter_map(|item| item.id.as_ref())
+        .collect();
+    let mut not_found = Vec::new();
+    for id in ids {
+        match found.get(id).cloned() {
+            Some(item) => batch.found.push(item),
+            None if skipped.contains(id) => {}
+            None => not_found.push(id.clone()),
+        }
+    }
+    batch.not_found = not_found;
+    Ok(batch)
+}
@@ -0,0 +1,237 @@
+use futures::stream::{self, BoxStream, Stream, StreamExt};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use crate::error::Error;
+use crate::utils::runtime;
+
+/// How many lookups of a bulk operation run at the same time, and how often they start
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BulkOptions {
+    /// Maximum number of lookups running at the same time, at least one always runs
+    pub concurrency: usize,
+    /// Minimum time between the start of two lookups, `None` starts them as soon as possible
+    pub min_interval: Option<Duration>,
+}
+
+impl Default for BulkOptions {
+    fn default() -> Self {
+        BulkOptions {
+            concurrency: 8,
+            min_interval: None,
+        }
+    }
+}
+
+impl BulkOptions {
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Starts at most `requests` lookups per `period`
+    pub fn with_rate_limit(mut self, requests: u32, period: Duration) -> Self {
+        self.min_interval = Some(period / requests.max(1));
+        self
+    }
+}
+
+/// How far a bulk operation went
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Progress {
+    pub completed: usize,
+    pub total: usize,
+}
+
+impl Progress {
+    pub fn is_done(&self) -> bool {
+        self.completed >= self.total
+    }
+}
+
+/// Results of a bulk operation, yielded as soon as each lookup finishes so not in the
+/// order of the input
+pub struct BulkStream<K, T> {
+    inner: BoxStream<'static, (K, Result<T, Error>)>,
+    progress: Progress,
+}
+
+impl<K, T> BulkStream<K, T> {
+    pub fn progress(&self) -> Progress {
+        self.progress
+    }
+}
+
+impl<K, T> Stream for BulkStream<K, T> {
+    type Item = (K, Result<T, Error>);
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let item = futures::ready!(self.inner.poll_next_unpin(cx));
+        if item.is_some() {
+            self.progress.completed += 1;
+        }
+        Poll::Ready(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.progress.total - self.progress.completed;
+        (remaining, Some(remaining))
+    }
+}
+
+/// Spaces out the start of the lookups, shared by all of them
+#[derive(Clone)]
+struct RateLimiter {
+    min_interval: Option<Duration>,
+    next_start: Arc<Mutex<Option<Instant>>>,
+}
+
+impl RateLimiter {
+    async fn wait(&self) {
+        let Some(min_interval) = self.min_interval else {
+            return;
+        };
+
+        let delay = {
+            let mut next_start = self.next_start.lock().unwrap();
+            let now = Instant::now();
+            let start = next_start.map_or(now, |next_start| next_start.max(now));
+            *next_start = Some(start + min_interval);
+            start - now
+        };
+        if !delay.is_zero() {
+            runtime::sleep(delay).await;
+        }
+    }
+}
+
+/// Runs a lookup for every key within the limits of the options
+pub(crate) fn run<K, T, F, Fut>(keys: Vec<K>, options: &BulkOptions, lookup: F) -> BulkStream<K, T>
+where
+    K: Clone + Send + 'static,
+    T: Send + 'static,
+    F: Fn(K) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<T, Error>> + Send + 'static,
+{
+    let limiter = RateLimiter {
+        min_interval: options.min_interval,
+        next_start: Arc::new(Mutex::new(None)),
+    };
+    let progress = Progress {
+        completed: 0,
+        total: keys.len(),
+    };
+
+    let inner = stream::iter(keys)
+        .map(move |key| {
+            let limiter = limiter.clone();
+            let lookup = lookup(key.clone());
+            async move {
+                limiter.wait().await;
+                (key, lookup.await)
+            }
+        })
+        .buffer_unordered(options.concurrency.max(1))
+        .boxed();
+
+    BulkStream { inner, progress }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn lookups_run_within_the_concurrency_limit() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+        let (running_lookups, max_running_lookups) = (running.clone(), max_running.clone());
+
+        let stream = run(
+            (0..10).collect(),
+            &BulkOptions::default().with_concurrency(3),
+            move |key: u32| {
+                let running = running_lookups.clone();
+                let max_running = max_running_lookups.clone();
+                async move {
+                    let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_running.fetch_max(now_running, Ordering::SeqCst);
+                    runtime::sleep(Duration::from_millis(10)).await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    Ok(key * 2)
+                }
+            },
+        );
+
+        let mut results: Vec<(u32, u32)> = stream
+            .map(|(key, result)| (key, result.unwrap()))
+            .collect()
+            .await;
+        results.sort();
+
+        assert_eq!(results.len(), 10);
+        assert!(results.iter().all(|(key, value)| *value == key * 2));
+        assert_eq!(max_running.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn lookups_are_rate_limited() {
+        let started = Instant::now();
+        let stream = run(
+            vec![1, 2, 3],
+            &BulkOptions::default().with_rate_limit(1, Duration::from_millis(30)),
+            |key: u32| async move { Ok(key) },
+        );
+
+        let results: Vec<_> = stream.collect().await;
+
+        assert_eq!(results.len(), 3);
+        assert!(started.elapsed() >= Duration::from_millis(60));
+    }
+
+    // The tokio timer needs a tokio runtime, the default one runs on any executor
+    #[cfg(not(feature = "tokio"))]
+    #[test]
+    fn lookups_run_without_a_tokio_runtime() {
+        let stream = run(
+            vec![1, 2],
+            &BulkOptions::default().with_rate_limit(1, Duration::from_millis(10)),
+            |key: u32| async move { Ok(key) },
+        );
+
+        let results: Vec<_> = futures::executor::block_on(stream.collect());
+
+        assert_eq!(results.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn progress_is_reported() {
+        let mut stream = run(vec![1, 2], &BulkOptions::default(), |key: u32| async move {
+            match key {
+                1 => Ok(key),
+                _ => Err(Error::NotFound),
+            }
+        });
+        assert_eq!(
+            stream.progress(),
+            Progress {
+                completed: 0,
+                total: 2
+            }
+        );
+
+        let (first_key, first) = stream.next().await.unwrap();
+        assert_eq!(first.is_ok(), first_key == 1);
+        assert_eq!(stream.progress().completed, 1);
+        let (second_key, second) = stream.next().await.unwrap();
+        assert_eq!(second.is_ok(), second_key == 1);
+        assert!(stream.progress().is_done());
+        assert!(stream.next().await.is_none());
+    }
+}
@@ -1,78 +1,281 @@
+use crate::error::Error;
 use crate::utils::http_client::HttpClientTrait;
-use crate::youtube::requests::channel::retrieve_channel_id;
+use crate::youtube::requests::channel::{
+    retrieve_channel_by_id, retrieve_channel_details, retrieve_channel_id,
+    retrieve_channel_id_by_username, retrieve_channels_by_ids,
+};
 use std::sync::Arc;
-use thiserror::Error;
 
+use super::batch::{self, BatchLookup};
+use super::bulk::{self, BulkOptions, BulkStream};
+use super::channel_id_store::{ChannelIdStore, StoredChannelId};
+use super::comment::{
+    CommentCursor, CommentFeed, CommentOrder, CommentThread, CommentThreadOptions,
+};
+use super::id::{ChannelId, Handle, PlaylistId};
+use super::listing::{ListingOptions, VideoListing};
+use super::page::Page;
+use super::parts::RequestOptions;
 use super::playlist::Playlist;
+use super::requests::comment::retrieve_comment_threads;
 use super::requests::playlist::retrieve_main_playlist_id;
 use super::requests::video::retrieve_latest_videos;
 use super::video::Video;
 
 // A youtube channel with some useful data
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Channel {
-    pub handle: String,
+    /// `None` when the channel has no handle, or only a legacy custom url
+    pub handle: Option<Handle>,
     pub title: String,
-    pub channel_id: String,
+    pub channel_id: ChannelId,
 }
 
-#[derive(Debug, Error)]
-#[error("{msg}")]
-pub struct ChannelError {
-    pub source: Option<anyhow::Error>,
-    pub msg: String,
+/// Description, branding and statistics of a channel
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelDetails {
+    /// `None` when left out by the parts or fields of `RequestOptions`, as is `published_at`
+    pub description: Option<String>,
+    pub custom_url: Option<String>,
+    pub country: Option<String>,
+    /// Creation date of the channel
+    pub published_at: Option<String>,
+    pub avatar_url: Option<String>,
+    pub banner_url: Option<String>,
+    pub keywords: Vec<String>,
+    /// Always `None` when the channel hides its subscriber count
+    pub subscriber_count: Option<u64>,
+    pub hidden_subscriber_count: bool,
+    pub video_count: Option<u64>,
+    pub view_count: Option<u64>,
+    /// Wikipedia urls describing the content of the channel
+    pub topic_categories: Vec<String>,
+    pub made_for_kids: Option<bool>,
 }
 
 impl Channel {
-    fn new(handle: String, title: String, channel_id: String) -> Self {
+    fn new(handle: impl Into<Option<Handle>>, title: String, channel_id: ChannelId) -> Self {
         Self {
-            handle,
+            handle: handle.into(),
             title,
             channel_id,
         }
     }
 
+    /// Resolves a handle to its channel, always with a request. `YoutubeClient` answers from
+    /// its `ChannelIdStore` first when it has one, see `initialize_with_store`
     pub async fn initialize<T: HttpClientTrait>(
-        handle: String,
+        handle: Handle,
         client: Arc<T>,
-    ) -> Result<Self, ChannelError> {
-        let (channel_id, title) =
-            retrieve_channel_id(&handle, &client)
-                .await
-                .map_err(|e| ChannelError {
-                    source: Some(e.into()),
-                    msg: "Failed to get channel id".to_owned(),
-                })?;
+    ) -> Result<Self, Error> {
+        let (channel_id, title) = retrieve_channel_id(handle.as_str(), &client).await?;
+
+        Ok(Self::new(
+            handle,
+            title,
+            ChannelId::new_unchecked(channel_id),
+        ))
+    }
+
+    /// Like `initialize`, but answers with the channel remembered by the store while it is
+    /// fresh, and remembers the channel after resolving the handle
+    pub async fn initialize_with_store<T: HttpClientTrait, S: ChannelIdStore + ?Sized>(
+        handle: Handle,
+        store: &S,
+        client: Arc<T>,
+    ) -> Result<Self, Error> {
+        // An unreadable store only costs a lookup
+        if let Ok(Some(stored)) = store.load(&handle) {
+            let age = stored.resolved_at.elapsed();
+            if age.is_ok_and(|age| age < store.max_age()) {
+                return Ok(Self::new(handle, stored.title, stored.channel_id));
+            }
+        }
+
+        let channel = Self::initialize(handle.clone(), client).await?;
+        let stored = StoredChannelId::new(channel.channel_id.clone(), channel.title.clone());
+        // A store that cannot be written must not fail the lookup
+        let _ = store.save(&handle, stored);
+
+        Ok(channel)
+    }
+
+    /// Looks up many handles within the concurrency and rate limits of the options, each
+    /// handle is yielded with its channel or error as soon as its lookup finishes
+    pub fn initialize_many<T: HttpClientTrait + 'static>(
+        handles: Vec<Handle>,
+        options: &BulkOptions,
+        client: Arc<T>,
+    ) -> BulkStream<Handle, Self> {
+        bulk::run(handles, options, move |handle| {
+            Self::initialize(handle, client.clone())
+        })
+    }
+
+    /// Retrieves a channel by its id, without a handle if the channel has no valid one
+    pub async fn from_id<T: HttpClientTrait>(
+        channel_id: ChannelId,
+        client: Arc<T>,
+    ) -> Result<Self, Error> {
+        let (handle, title) = retrieve_channel_by_id(channel_id.as_str(), &client).await?;
 
         Ok(Self::new(handle, title, channel_id))
     }
 
-    async fn get_main_playlist_id<T: HttpClientTrait>(
+    /// Retrieves many channels at once, 50 per request, along with the ids that do not exist
+    pub async fn from_ids<T: HttpClientTrait>(
+        channel_ids: &[ChannelId],
+        client: Arc<T>,
+    ) -> Result<BatchLookup<ChannelId, Self>, Error> {
+        let fetch = |chunk: Vec<ChannelId>| {
+            let client = client.clone();
+            async move {
+                let ids: Vec<&str> = chunk.iter().map(ChannelId::as_str).collect();
+                let (channels, skipped) = retrieve_channels_by_ids(&ids, &client).await?;
+
+                Ok((
+                    channels
+                        .into_iter()
+                        .map(|(channel_id, handle, title)| Self::new(handle, title, channel_id))
+                        .collect(),
+                    skipped,
+                ))
+            }
+        };
+
+        batch::lookup(channel_ids, fetch, |channel| &channel.channel_id).await
+    }
+
+    /// Retrieves a channel by its legacy username, https://www.youtube.com/user/{username}
+    pub async fn from_username<T: HttpClientTrait>(
+        username: &str,
+        client: Arc<T>,
+    ) -> Result<Self, Error> {
+        let channel_id = retrieve_channel_id_by_username(username, &client).await?;
+
+        Self::from_id(ChannelId::new_unchecked(channel_id), client).await
+    }
+
+    /// Retrieves the description, branding and statistics of this channel
+    pub async fn details<T: HttpClientTrait>(
         &self,
         client: Arc<T>,
-    ) -> Result<Playlist, ChannelError> {
-        let playlist_id = retrieve_main_playlist_id(&self.channel_id, &client)
+    ) -> Result<ChannelDetails, Error> {
+        self.details_with_options(&RequestOptions::default(), client)
             .await
-            .map_err(|e| ChannelError {
-                source: Some(e.into()),
-                msg: "Failed to get playlist id".to_owned(),
-            })?;
+    }
+
+    /// Retrieves the details of this channel with only the parts and fields of the options,
+    /// the counts of the sections that are not requested are `None`
+    pub async fn details_with_options<T: HttpClientTrait>(
+        &self,
+        options: &RequestOptions,
+        client: Arc<T>,
+    ) -> Result<ChannelDetails, Error> {
+        retrieve_channel_details(self.channel_id.as_str(), options, &client)
+            .await
+            .map_err(Error::from)
+    }
+
+    /// Retrieves a page of the comment threads of every video of this channel, newest first
+    /// whatever the order of the options
+    pub async fn comment_threads<T: HttpClientTrait>(
+        &self,
+        options: &CommentThreadOptions,
+        client: Arc<T>,
+    ) -> Result<Page<CommentThread>, Error> {
+        let options = CommentThreadOptions {
+            order: CommentOrder::Time,
+            ..options.clone()
+        };
+
+        retrieve_comment_threads(
+            ("allThreadsRelatedToChannelId", self.channel_id.as_str()),
+            &options,
+            &client,
+        )
+        .await
+        .map_err(Error::from)
+    }
+
+    /// Retrieves the comment threads posted on the videos of this channel since the cursor,
+    /// going through pages until it is reached. Without a position the cursor only gets the
+    /// first page, poll again with the returned cursor to get the threads posted in between.
+    /// The feed is threads-only: replies posted to a thread after it was returned are not
+    pub async fn comment_threads_since<T: HttpClientTrait>(
+        &self,
+        cursor: &CommentCursor,
+        options: &CommentThreadOptions,
+        client: Arc<T>,
+    ) -> Result<CommentFeed, Error> {
+        let mut options = CommentThreadOptions {
+            page_token: None,
+            ..options.clone()
+        };
+        let mut threads = Vec::new();
+        loop {
+            let page = self.comment_threads(&options, client.clone()).await?;
+            let reached_cursor =
+                cursor.since.is_none() || page.items.iter().any(|thread| cursor.is_past(thread));
+            threads.extend(
+                page.items
+                    .into_iter()
+                    .filter(|thread| cursor.is_new(thread)),
+            );
+
+            match page.next_page_token {
+                Some(token) if !reached_cursor => options = options.with_page_token(&token),
+                _ => break,
+            }
+        }
+
+        Ok(CommentFeed {
+            cursor: cursor.advance(&threads),
+            threads,
+        })
+    }
+
+    async fn get_main_playlist_id<T: HttpClientTrait>(
+        &self,
+        client: Arc<T>,
+    ) -> Result<Playlist, Error> {
+        let playlist_id = retrieve_main_playlist_id(self.channel_id.as_str(), &client).await?;
 
-        Ok(Playlist::new(self.channel_id.clone(), playlist_id))
+        Ok(Playlist::new(
+            self.channel_id.clone(),
+            PlaylistId::new_unchecked(playlist_id),
+        ))
     }
 
+    /// Returns the latest videos of the channel, skipping the items that are not valid videos
     pub async fn get_latest_videos<T: HttpClientTrait>(
         &self,
         number_of_videos: i32,
         client: Arc<T>,
-    ) -> Result<Vec<Video>, ChannelError> {
-        let playlist = self.get_main_playlist_id(client.clone()).await?;
-        retrieve_latest_videos(&playlist.playlist_id, number_of_videos, client)
+    ) -> Result<Vec<Video>, Error> {
+        self.get_latest_videos_with_options(number_of_videos, &ListingOptions::default(), client)
             .await
-            .map_err(|e| ChannelError {
-                source: Some(e.into()),
-                msg: "Failed to get videos from channel".to_owned(),
-            })
+            .map(|listing| listing.videos)
+    }
+
+    /// Returns the latest videos of the channel along with the items that were skipped
+    pub async fn get_latest_videos_with_options<T: HttpClientTrait>(
+        &self,
+        number_of_videos: i32,
+        options: &ListingOptions,
+        client: Arc<T>,
+    ) -> Result<VideoListing, Error> {
+        let playlist = self.get_main_playlist_id(client.clone()).await?;
+        retrieve_latest_videos(
+            playlist.playlist_id.as_str(),
+            number_of_videos,
+            options,
+            client,
+        )
+        .await
+        .map_err(Error::from)
     }
 }
 
@@ -80,6 +283,12 @@ impl Channel {
 mod tests {
     use super::*;
     use crate::test_utils::create_client_with_responses;
+    use crate::youtube::channel_id_store::MemoryChannelIdStore;
+    use crate::youtube::listing::{ItemErrorKind, UnavailablePolicy};
+    use crate::youtube::parts::Part;
+    use crate::youtube::video::Availability;
+    use futures::StreamExt;
+    use std::time::Duration;
 
     #[tokio::test]
     async fn channel_initialization_succeeds_with_valid_id() {
@@ -104,13 +313,148 @@ mod tests {
             }"#,
         ])
         .await;
-        let channel = Channel::initialize("channel1".to_string(), client)
+        let channel = Channel::initialize("channel1".parse().unwrap(), client)
             .await
             .ok()
             .unwrap();
         assert_eq!(channel.channel_id, "channel_id");
         assert_eq!(channel.title, "Channel title");
-        assert_eq!(channel.handle, "channel1");
+        assert_eq!(channel.handle.unwrap(), "channel1");
+    }
+
+    const CHANNEL1_SEARCH_RESPONSE: &str = r#"{
+        "items": [
+            {
+                "snippet": {
+                    "channelId": "id_channel1",
+                    "channelTitle": "Channel title",
+                    "channelHandle": "@channel1"
+                }
+            }
+        ]
+    }"#;
+
+    #[tokio::test]
+    async fn channel_initialization_uses_the_channel_id_store() {
+        let store = MemoryChannelIdStore::new();
+        let client = create_client_with_responses(vec![CHANNEL1_SEARCH_RESPONSE]).await;
+
+        let resolved =
+            Channel::initialize_with_store("channel1".parse().unwrap(), &store, client.clone())
+                .await
+                .unwrap();
+        let remembered =
+            Channel::initialize_with_store("Channel1".parse().unwrap(), &store, client.clone())
+                .await
+                .unwrap();
+
+        assert_eq!(client.requested_urls().len(), 1);
+        assert_eq!(remembered.channel_id, resolved.channel_id);
+        assert_eq!(remembered.title, "Channel title");
+    }
+
+    #[tokio::test]
+    async fn handles_are_resolved_whatever_their_case() {
+        let client = create_client_with_responses(vec![CHANNEL1_SEARCH_RESPONSE]).await;
+
+        let channel = Channel::initialize("Channel1".parse().unwrap(), client)
+            .await
+            .unwrap();
+
+        assert_eq!(channel.channel_id, "id_channel1");
+    }
+
+    struct ReadOnlyStore;
+
+    impl ChannelIdStore for ReadOnlyStore {
+        fn load(&self, _handle: &Handle) -> std::io::Result<Option<StoredChannelId>> {
+            Ok(None)
+        }
+
+        fn save(&self, _handle: &Handle, _channel: StoredChannelId) -> std::io::Result<()> {
+            Err(std::io::ErrorKind::PermissionDenied.into())
+        }
+    }
+
+    #[tokio::test]
+    async fn channel_id_store_write_failures_do_not_fail_the_lookup() {
+        let client = create_client_with_responses(vec![CHANNEL1_SEARCH_RESPONSE]).await;
+
+        let channel =
+            Channel::initialize_with_store("channel1".parse().unwrap(), &ReadOnlyStore, client)
+                .await
+                .unwrap();
+
+        assert_eq!(channel.channel_id, "id_channel1");
+    }
+
+    #[tokio::test]
+    async fn stale_channel_ids_are_resolved_again() {
+        let store = MemoryChannelIdStore::new().with_max_age(Duration::ZERO);
+        store
+            .save(
+                &"channel1".parse().unwrap(),
+                StoredChannelId::new(ChannelId::new_unchecked("old_id"), "Old title".to_string()),
+            )
+            .unwrap();
+        let client = create_client_with_responses(vec![CHANNEL1_SEARCH_RESPONSE]).await;
+
+        let channel =
+            Channel::initialize_with_store("channel1".parse().unwrap(), &store, client.clone())
+                .await
+                .unwrap();
+
+        assert_eq!(channel.channel_id, "id_channel1");
+        assert_eq!(
+            store
+                .load(&"channel1".parse().unwrap())
+                .unwrap()
+                .unwrap()
+                .channel_id,
+            "id_channel1"
+        );
+    }
+
+    #[tokio::test]
+    async fn many_channels_are_initialized() {
+        let response = r#"{
+            "items": [
+                {
+                    "snippet": {
+                        "channelId": "id_channel1",
+                        "channelTitle": "Channel 1",
+                        "channelHandle": "@channel1"
+                    }
+                },
+                {
+                    "snippet": {
+                        "channelId": "id_channel2",
+                        "channelTitle": "Channel 2",
+                        "channelHandle": "@channel2"
+                    }
+                }
+            ]
+        }"#;
+        let client = create_client_with_responses(vec![response; 3]).await;
+        let handles: Vec<Handle> = ["channel1", "channel2", "missing"]
+            .iter()
+            .map(|handle| handle.parse().unwrap())
+            .collect();
+
+        let mut stream =
+            Channel::initialize_many(handles, &BulkOptions::default().with_concurrency(2), client);
+        let mut results = Vec::new();
+        while let Some((handle, result)) = stream.next().await {
+            results.push((handle.to_string(), result));
+        }
+        results.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert!(stream.progress().is_done());
+        assert_eq!(results[0].0, "@channel1");
+        assert_eq!(results[0].1.as_ref().unwrap().title, "Channel 1");
+        assert_eq!(results[1].0, "@channel2");
+        assert_eq!(results[1].1.as_ref().unwrap().channel_id, "id_channel2");
+        assert!(matches!(results[2].1, Err(Error::NotFound)));
     }
 
     #[tokio::test]
@@ -123,8 +467,8 @@ mod tests {
         ]}"#,
         ])
         .await;
-        let channel = Channel::initialize("channel1".to_string(), client).await;
-        assert!(channel.is_err());
+        let channel = Channel::initialize("channel1".parse().unwrap(), client).await;
+        assert!(matches!(channel, Err(Error::Decode(_))));
     }
 
     #[tokio::test]
@@ -143,16 +487,341 @@ mod tests {
             }"#,
         ])
         .await;
-        let channel = Channel::initialize("channel1".to_string(), client).await;
-        assert!(channel.is_err());
+        let channel = Channel::initialize("channel1".parse().unwrap(), client).await;
+        assert!(matches!(channel, Err(Error::NotFound)));
+    }
+
+    #[tokio::test]
+    async fn channels_are_retrieved_by_many_ids() {
+        let client = create_client_with_responses(vec![
+            r#"{"items": [
+                {
+                    "id": "UC_x5XG1OV2P6uZZ5FSM9Ttw",
+                    "snippet": {"title": "Google for Developers", "customUrl": "Google Developers"}
+                },
+                {
+                    "id": "UCuAXFkgsw1L7xaCfnd5JJOw",
+                    "snippet": {"title": "Rick Astley", "customUrl": "@rickastleyyt"}
+                }
+            ]}"#,
+        ])
+        .await;
+        let ids: Vec<ChannelId> = [
+            "UCuAXFkgsw1L7xaCfnd5JJOw",
+            "UCaaaaaaaaaaaaaaaaaaaaaa",
+            "UC_x5XG1OV2P6uZZ5FSM9Ttw",
+        ]
+        .iter()
+        .map(|id| id.parse().unwrap())
+        .collect();
+
+        let batch = Channel::from_ids(&ids, client).await.unwrap();
+
+        assert_eq!(batch.found.len(), 2);
+        assert_eq!(batch.found[0].title, "Rick Astley");
+        assert_eq!(batch.found[0].handle.as_ref().unwrap(), "rickastleyyt");
+        assert_eq!(batch.found[1].channel_id, ids[2]);
+        assert_eq!(batch.found[1].handle, None);
+        assert_eq!(batch.not_found, vec![ids[1].clone()]);
+    }
+
+    #[tokio::test]
+    async fn channel_details_are_returned() {
+        let channel = Channel::new(
+            "channel1".parse::<Handle>().unwrap(),
+            "title".to_string(),
+            ChannelId::new_unchecked("id_channel1"),
+        );
+        let client = create_client_with_responses(vec![
+            r#"{
+                "items": [
+                    {
+                        "id": "id_channel1",
+                        "snippet": {
+                            "title": "title",
+                            "description": "Channel description",
+                            "customUrl": "@channel1",
+                            "publishedAt": "2015-05-25T08:21:44Z",
+                            "country": "ES",
+                            "thumbnails": {
+                                "default": {
+                                    "url": "https://yt3.ggpht.com/default"
+                                },
+                                "high": {
+                                    "url": "https://yt3.ggpht.com/high"
+                                }
+                            }
+                        },
+                        "statistics": {
+                            "viewCount": "1000",
+                            "subscriberCount": "100",
+                            "hiddenSubscriberCount": false,
+                            "videoCount": "10"
+                        },
+                        "brandingSettings": {
+                            "channel": {
+                                "keywords": "music \"rick astley\" pop"
+                            },
+                            "image": {
+                                "bannerExternalUrl": "https://yt3.googleusercontent.com/banner"
+                            }
+                        },
+                        "topicDetails": {
+                            "topicCategories": ["https://en.wikipedia.org/wiki/Music"]
+                        },
+                        "status": {
+                            "madeForKids": false
+                        }
+                    }
+                ]
+            }"#,
+        ])
+        .await;
+
+        let details = channel.details(client).await.unwrap();
+
+        assert_eq!(details.description.as_deref(), Some("Channel description"));
+        assert_eq!(details.custom_url.as_deref(), Some("@channel1"));
+        assert_eq!(details.country.as_deref(), Some("ES"));
+        assert_eq!(
+            details.published_at.as_deref(),
+            Some("2015-05-25T08:21:44Z")
+        );
+        assert_eq!(
+            details.avatar_url.as_deref(),
+            Some("https://yt3.ggpht.com/high")
+        );
+        assert_eq!(
+            details.banner_url.as_deref(),
+            Some("https://yt3.googleusercontent.com/banner")
+        );
+        assert_eq!(details.keywords, vec!["music", "rick astley", "pop"]);
+        assert_eq!(details.subscriber_count, Some(100));
+        assert!(!details.hidden_subscriber_count);
+        assert_eq!(details.video_count, Some(10));
+        assert_eq!(details.view_count, Some(1000));
+        assert_eq!(
+            details.topic_categories,
+            vec!["https://en.wikipedia.org/wiki/Music"]
+        );
+        assert_eq!(details.made_for_kids, Some(false));
+    }
+
+    #[tokio::test]
+    async fn channel_details_do_not_include_hidden_subscriber_count() {
+        let channel = Channel::new(
+            "channel1".parse::<Handle>().unwrap(),
+            "title".to_string(),
+            ChannelId::new_unchecked("id_channel1"),
+        );
+        let client = create_client_with_responses(vec![
+            r#"{
+                "items": [
+                    {
+                        "snippet": {
+                            "title": "title",
+                            "publishedAt": "2015-05-25T08:21:44Z"
+                        },
+                        "statistics": {
+                            "viewCount": "1000",
+                            "subscriberCount": "0",
+                            "hiddenSubscriberCount": true,
+                            "videoCount": "10"
+                        }
+                    }
+                ]
+            }"#,
+        ])
+        .await;
+
+        let details = channel.details(client).await.unwrap();
+
+        assert!(details.hidden_subscriber_count);
+        assert_eq!(details.subscriber_count, None);
+        assert!(details.keywords.is_empty());
+        assert_eq!(details.avatar_url, None);
+    }
+
+    fn thread_item(id: &str, published_at: &str) -> String {
+        format!(
+            r#"{{"id": "{}", "snippet": {{
+                "videoId": "dQw4w9WgXcQ",
+                "topLevelComment": {{"id": "{}", "snippet": {{"publishedAt": "{}"}}}}
+            }}}}"#,
+            id, id, published_at
+        )
+    }
+
+    fn thread_page(items: &[String], next_page_token: Option<&str>) -> String {
+        let token = next_page_token
+            .map(|token| format!(r#""nextPageToken": "{}","#, token))
+            .unwrap_or_default();
+        format!(r#"{{{} "items": [{}]}}"#, token, items.join(","))
+    }
+
+    fn comment_channel() -> Channel {
+        Channel::new(
+            "rickastleyyt".parse::<Handle>().unwrap(),
+            "Rick Astley".to_string(),
+            ChannelId::new_unchecked("UCuAXFkgsw1L7xaCfnd5JJOw"),
+        )
+    }
+
+    #[tokio::test]
+    async fn channel_comment_threads_are_sorted_by_time() {
+        let response = thread_page(&[thread_item("a", "2023-01-01T10:00:00Z")], None);
+        let client = create_client_with_responses(vec![&response]).await;
+        let options = CommentThreadOptions::default().with_order(CommentOrder::Relevance);
+
+        let page = comment_channel()
+            .comment_threads(&options, client.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            client.requested_urls(),
+            vec!["https://yt.lemnoslife.com/noKey/commentThreads?part=snippet%2Creplies&allThreadsRelatedToChannelId=UCuAXFkgsw1L7xaCfnd5JJOw&order=time&textFormat=html"]
+        );
+        assert_eq!(page.items[0].video_id.as_ref().unwrap(), "dQw4w9WgXcQ");
+        assert!(!page.has_next_page());
+    }
+
+    #[tokio::test]
+    async fn channel_comment_threads_are_polled_since_a_cursor() {
+        let first_page = thread_page(
+            &[
+                thread_item("d", "2023-01-05T10:00:00Z"),
+                thread_item("c", "2023-01-03T10:00:00Z"),
+            ],
+            Some("page2"),
+        );
+        let second_page = thread_page(
+            &[
+                thread_item("b", "2023-01-02T10:00:00Z"),
+                thread_item("b2", "2023-01-02T10:00:00Z"),
+                thread_item("a", "2023-01-01T10:00:00Z"),
+            ],
+            Some("page3"),
+        );
+        let client = create_client_with_responses(vec![&second_page, &first_page]).await;
+        let cursor = CommentCursor {
+            since: Some("2023-01-02T10:00:00Z".to_string()),
+            seen_ids: vec!["b".to_string()],
+        };
+
+        let feed = comment_channel()
+            .comment_threads_since(&cursor, &CommentThreadOptions::default(), client.clone())
+            .await
+            .unwrap();
+
+        let ids: Vec<&str> = feed
+            .threads
+            .iter()
+            .map(|thread| thread.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["d", "c", "b2"]);
+        assert_eq!(client.requested_urls().len(), 2);
+        assert_eq!(
+            feed.cursor,
+            CommentCursor {
+                since: Some("2023-01-05T10:00:00Z".to_string()),
+                seen_ids: vec!["d".to_string()],
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn cursors_are_normalized_to_utc() {
+        let response = thread_page(
+            &[
+                thread_item("c", "2023-01-02T10:00:01Z"),
+                thread_item("b", "2023-01-02T10:00:00Z"),
+                thread_item("a", "2023-01-02T09:59:59Z"),
+            ],
+            Some("page2"),
+        );
+        let client = create_client_with_responses(vec![&response]).await;
+        let cursor = CommentCursor::since("2023-01-02T11:59:59.5+02:00").unwrap();
+
+        let feed = comment_channel()
+            .comment_threads_since(&cursor, &CommentThreadOptions::default(), client)
+            .await
+            .unwrap();
+
+        assert_eq!(cursor.since.as_deref(), Some("2023-01-02T09:59:59.5Z"));
+        let ids: Vec<&str> = feed
+            .threads
+            .iter()
+            .map(|thread| thread.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["c", "b"]);
+        assert!(matches!(
+            CommentCursor::since("2023-01-02"),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn first_poll_of_channel_comment_threads_gets_one_page() {
+        let response = thread_page(
+            &[
+                thread_item("b", "2023-01-02T10:00:00Z"),
+                thread_item("a", "2023-01-02T10:00:00Z"),
+            ],
+            Some("page2"),
+        );
+        let client = create_client_with_responses(vec![&response]).await;
+
+        let feed = comment_channel()
+            .comment_threads_since(
+                &CommentCursor::default(),
+                &CommentThreadOptions::default(),
+                client.clone(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(feed.threads.len(), 2);
+        assert_eq!(client.requested_urls().len(), 1);
+        assert_eq!(feed.cursor.since.as_deref(), Some("2023-01-02T10:00:00Z"));
+        assert_eq!(feed.cursor.seen_ids, vec!["b", "a"]);
+    }
+
+    #[tokio::test]
+    async fn channel_details_request_only_the_parts_of_the_options() {
+        let channel = Channel::new(
+            "channel1".parse::<Handle>().unwrap(),
+            "title".to_string(),
+            ChannelId::new_unchecked("id_channel1"),
+        );
+        let client = create_client_with_responses(vec![
+            r#"{"items": [{"snippet": {"description": "Channel description"}}]}"#,
+        ])
+        .await;
+        let options =
+            RequestOptions::with_parts(&[Part::Snippet]).with_fields("items(snippet(description))");
+
+        let details = channel
+            .details_with_options(&options, client.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            client.requested_urls(),
+            vec!["https://yt.lemnoslife.com/noKey/channels?part=snippet&fields=items%28snippet%28description%29%29&id=id_channel1"]
+        );
+        assert_eq!(details.description.as_deref(), Some("Channel description"));
+        assert_eq!(details.published_at, None);
+        assert_eq!(details.view_count, None);
+        assert_eq!(details.made_for_kids, None);
     }
 
     #[tokio::test]
     async fn main_playlist_is_found_for_a_channel() {
         let channel = Channel::new(
-            "channel1".to_string(),
+            "channel1".parse::<Handle>().unwrap(),
             "title".to_string(),
-            "id_channel1".to_string(),
+            ChannelId::new_unchecked("id_channel1"),
         );
         let client = create_client_with_responses(vec![
             r#"{"items": [
@@ -174,9 +843,9 @@ mod tests {
     #[tokio::test]
     async fn main_playlist_search_fails_if_channel_do_not_have_any_playlist() {
         let channel = Channel::new(
-            "channel1".to_string(),
+            "channel1".parse::<Handle>().unwrap(),
             "title".to_string(),
-            "id_channel1".to_string(),
+            ChannelId::new_unchecked("id_channel1"),
         );
         let client = create_client_with_responses(vec![
             r#"{"items": [
@@ -191,16 +860,15 @@ mod tests {
         ])
         .await;
         let result = channel.get_main_playlist_id(client).await;
-        assert!(result.is_err());
-        assert_eq!(result.err().unwrap().msg, "Failed to get playlist id");
+        assert!(matches!(result, Err(Error::Decode(_))));
     }
 
     #[tokio::test]
     async fn videos_from_channel_are_returned() {
         let channel = Channel::new(
-            "channel1".to_string(),
+            "channel1".parse::<Handle>().unwrap(),
             "title".to_string(),
-            "id_channel1".to_string(),
+            ChannelId::new_unchecked("id_channel1"),
         );
         let playlist_response = r#"{"items": [
             {
@@ -252,18 +920,357 @@ mod tests {
         let videos = channel.get_latest_videos(2, client).await.unwrap();
 
         assert_eq!(videos.len(), 2);
-        let video1 = videos.get(0).unwrap();
+        let video1 = videos.first().unwrap();
         assert_eq!(video1.url(), "https://www.youtube.com/watch?v=dQw4w9WgXcQ");
-        assert_eq!(video1.title, "Video Title 1");
-        assert_eq!(video1.description, "Description video 1");
+        assert_eq!(video1.title.as_deref(), Some("Video Title 1"));
+        assert_eq!(video1.description.as_deref(), Some("Description video 1"));
+    }
+
+    #[tokio::test]
+    async fn videos_without_high_thumbnail_are_returned() {
+        let channel = Channel::new(
+            "channel1".parse::<Handle>().unwrap(),
+            "title".to_string(),
+            ChannelId::new_unchecked("id_channel1"),
+        );
+        let playlist_response = r#"{"items": [
+            {
+                "contentDetails": {
+                    "relatedPlaylists": {
+                        "uploads": "playlist_id1"
+                    }
+                }
+            }
+        ]}"#;
+
+        let video_response = r#"{
+            "items": [
+                {
+                    "snippet": {
+                        "publishedAt": "2023-09-21T17:02:18Z",
+                        "title": "Video Title 1",
+                        "description": "Description video 1",
+                        "thumbnails": {
+                            "default": {
+                                "url": "https://i.ytimg.com/vi/dQw4w9WgXcQ/default.jpg"
+                            }
+                        },
+                        "resourceId": {
+                            "videoId": "dQw4w9WgXcQ"
+                        }
+                    }
+                }
+            ]
+        }"#;
+
+        let client = create_client_with_responses(vec![video_response, playlist_response]).await;
+
+        let videos = channel.get_latest_videos(1, client).await.unwrap();
+
+        let video = videos.first().unwrap();
+        assert_eq!(
+            video.thumbnail,
+            "https://i.ytimg.com/vi/dQw4w9WgXcQ/hqdefault.jpg"
+        );
+        assert_eq!(video.thumbnails.best().width, 120);
+    }
+
+    const PLAYLIST_RESPONSE: &str = r#"{"items": [
+        {
+            "contentDetails": {
+                "relatedPlaylists": {
+                    "uploads": "playlist_id1"
+                }
+            }
+        }
+    ]}"#;
+
+    const VIDEOS_WITH_INVALID_ITEMS_RESPONSE: &str = r#"{
+        "items": [
+            {
+                "snippet": {
+                    "publishedAt": "2023-09-21T17:02:18Z",
+                    "title": "Video Title 1",
+                    "description": "Description video 1",
+                    "resourceId": {
+                        "videoId": "dQw4w9WgXcQ"
+                    }
+                }
+            },
+            {
+                "snippet": {
+                    "publishedAt": "2023-09-18T18:20:58Z",
+                    "title": "Video Title 2",
+                    "description": "Description video 2",
+                    "thumbnails": {
+                        "default": "not a thumbnail"
+                    },
+                    "resourceId": {
+                        "videoId": "9bZkp7q19f0"
+                    }
+                }
+            },
+            {
+                "snippet": {
+                    "publishedAt": "2023-09-17T18:20:58Z",
+                    "title": "Video Title 3",
+                    "description": "Description video 3"
+                }
+            }
+        ]
+    }"#;
+
+    #[tokio::test]
+    async fn invalid_videos_from_channel_are_skipped() {
+        let channel = Channel::new(
+            "channel1".parse::<Handle>().unwrap(),
+            "title".to_string(),
+            ChannelId::new_unchecked("id_channel1"),
+        );
+        let client = create_client_with_responses(vec![
+            VIDEOS_WITH_INVALID_ITEMS_RESPONSE,
+            PLAYLIST_RESPONSE,
+        ])
+        .await;
+
+        let listing = channel
+            .get_latest_videos_with_options(3, &ListingOptions::default(), client)
+            .await
+            .unwrap();
+
+        assert_eq!(listing.videos.len(), 1);
+        assert_eq!(listing.videos[0].id, "dQw4w9WgXcQ");
+        assert_eq!(listing.skipped.len(), 2);
+        assert_eq!(listing.skipped[0].index, 1);
+        assert_eq!(listing.skipped[0].video_id.as_ref().unwrap(), "9bZkp7q19f0");
+        assert!(matches!(
+            &listing.skipped[0].kind,
+            ItemErrorKind::Malformed { path, .. } if path == "snippet.thumbnails.default"
+        ));
+        assert_eq!(listing.skipped[1].index, 2);
+        assert_eq!(listing.skipped[1].kind, ItemErrorKind::MissingVideoId);
+    }
+
+    #[tokio::test]
+    async fn invalid_videos_from_channel_fail_in_strict_mode() {
+        let channel = Channel::new(
+            "channel1".parse::<Handle>().unwrap(),
+            "title".to_string(),
+            ChannelId::new_unchecked("id_channel1"),
+        );
+        let client = create_client_with_responses(vec![
+            VIDEOS_WITH_INVALID_ITEMS_RESPONSE,
+            PLAYLIST_RESPONSE,
+        ])
+        .await;
+
+        let result = channel
+            .get_latest_videos_with_options(3, &ListingOptions::strict(), client)
+            .await;
+
+        match result {
+            Err(Error::Decode(error)) => {
+                assert_eq!(error.path, "items[1].snippet.thumbnails.default")
+            }
+            _ => panic!("Expected a decode error"),
+        }
+    }
+
+    const VIDEOS_WITH_UNAVAILABLE_ITEMS_RESPONSE: &str = r#"{
+        "items": [
+            {
+                "snippet": {
+                    "publishedAt": "2023-09-21T17:02:18Z",
+                    "title": "Video Title 1",
+                    "description": "Description video 1",
+                    "resourceId": {
+                        "videoId": "dQw4w9WgXcQ"
+                    }
+                },
+                "status": {
+                    "privacyStatus": "public"
+                }
+            },
+            {
+                "snippet": {
+                    "publishedAt": "2023-09-18T18:20:58Z",
+                    "title": "Private video",
+                    "description": "This video is private.",
+                    "resourceId": {
+                        "videoId": "9bZkp7q19f0"
+                    }
+                },
+                "status": {
+                    "privacyStatus": "private"
+                }
+            },
+            {
+                "snippet": {
+                    "publishedAt": "2023-09-17T18:20:58Z",
+                    "title": "Deleted video",
+                    "description": "This video is unavailable.",
+                    "resourceId": {
+                        "videoId": "kJQP7kiw5Fk"
+                    }
+                },
+                "status": {
+                    "privacyStatus": "privacyStatusUnspecified"
+                }
+            },
+            {
+                "snippet": {
+                    "publishedAt": "2023-09-16T18:20:58Z",
+                    "title": "Video Title 4",
+                    "description": "Description video 4",
+                    "resourceId": {
+                        "videoId": "JGwWNGJdvx8"
+                    }
+                },
+                "status": {
+                    "privacyStatus": "privacyStatusUnspecified"
+                }
+            }
+        ]
+    }"#;
+
+    #[tokio::test]
+    async fn unavailable_videos_from_channel_are_included_by_default() {
+        let channel = Channel::new(
+            "channel1".parse::<Handle>().unwrap(),
+            "title".to_string(),
+            ChannelId::new_unchecked("id_channel1"),
+        );
+        let client = create_client_with_responses(vec![
+            VIDEOS_WITH_UNAVAILABLE_ITEMS_RESPONSE,
+            PLAYLIST_RESPONSE,
+        ])
+        .await;
+
+        let listing = channel
+            .get_latest_videos_with_options(4, &ListingOptions::default(), client)
+            .await
+            .unwrap();
+
+        let availabilities: Vec<Availability> = listing
+            .videos
+            .iter()
+            .map(|video| video.availability)
+            .collect();
+        assert_eq!(
+            availabilities,
+            vec![
+                Availability::Available,
+                Availability::Private,
+                Availability::Deleted,
+                Availability::Unavailable
+            ]
+        );
+        assert!(listing.skipped.is_empty());
+    }
+
+    #[tokio::test]
+    async fn unavailable_videos_from_channel_can_be_excluded() {
+        let channel = Channel::new(
+            "channel1".parse::<Handle>().unwrap(),
+            "title".to_string(),
+            ChannelId::new_unchecked("id_channel1"),
+        );
+        let client = create_client_with_responses(vec![
+            VIDEOS_WITH_UNAVAILABLE_ITEMS_RESPONSE,
+            PLAYLIST_RESPONSE,
+        ])
+        .await;
+        let options = ListingOptions::default().with_unavailable(UnavailablePolicy::Exclude);
+
+        let listing = channel
+            .get_latest_videos_with_options(4, &options, client)
+            .await
+            .unwrap();
+
+        assert_eq!(listing.videos.len(), 1);
+        assert_eq!(listing.videos[0].id, "dQw4w9WgXcQ");
+        assert!(listing.skipped.is_empty());
+    }
+
+    #[tokio::test]
+    async fn unavailable_videos_from_channel_can_be_reported() {
+        let channel = Channel::new(
+            "channel1".parse::<Handle>().unwrap(),
+            "title".to_string(),
+            ChannelId::new_unchecked("id_channel1"),
+        );
+        let client = create_client_with_responses(vec![
+            VIDEOS_WITH_UNAVAILABLE_ITEMS_RESPONSE,
+            PLAYLIST_RESPONSE,
+        ])
+        .await;
+        let options = ListingOptions::default().with_unavailable(UnavailablePolicy::Report);
+
+        let listing = channel
+            .get_latest_videos_with_options(4, &options, client)
+            .await
+            .unwrap();
+
+        assert_eq!(listing.videos.len(), 1);
+        assert_eq!(listing.skipped.len(), 3);
+        assert_eq!(listing.skipped[0].index, 1);
+        assert_eq!(listing.skipped[0].video_id.as_ref().unwrap(), "9bZkp7q19f0");
+        assert_eq!(
+            listing.skipped[0].kind,
+            ItemErrorKind::Unavailable(Availability::Private)
+        );
+        assert_eq!(
+            listing.skipped[1].kind,
+            ItemErrorKind::Unavailable(Availability::Deleted)
+        );
+        assert_eq!(
+            listing.skipped[2].kind,
+            ItemErrorKind::Unavailable(Availability::Unavailable)
+        );
+    }
+
+    #[tokio::test]
+    async fn public_videos_with_a_placeholder_title_are_available() {
+        let channel = Channel::new(
+            "channel1".parse::<Handle>().unwrap(),
+            "title".to_string(),
+            ChannelId::new_unchecked("id_channel1"),
+        );
+        let videos_response = r#"{"items": [
+            {
+                "snippet": {
+                    "publishedAt": "2023-09-21T17:02:18Z",
+                    "title": "Private video",
+                    "description": "A video about privacy",
+                    "resourceId": {
+                        "videoId": "dQw4w9WgXcQ"
+                    }
+                },
+                "status": {
+                    "privacyStatus": "public"
+                }
+            }
+        ]}"#;
+        let client = create_client_with_responses(vec![videos_response, PLAYLIST_RESPONSE]).await;
+
+        let listing = channel
+            .get_latest_videos_with_options(1, &ListingOptions::default(), client)
+            .await
+            .unwrap();
+
+        assert_eq!(listing.videos[0].availability, Availability::Available);
+        assert_eq!(
+            ItemErrorKind::Unavailable(Availability::Private).to_string(),
+            "video is private"
+        );
     }
 
     #[tokio::test]
     async fn videos_from_channel_cannot_be_retrieved_when_response_is_empty() {
         let channel = Channel::new(
-            "channel1".to_string(),
+            "channel1".parse::<Handle>().unwrap(),
             "title".to_string(),
-            "id_channel1".to_string(),
+            ChannelId::new_unchecked("id_channel1"),
         );
         let playlist_response = r#"{"items": [
             {
@@ -280,10 +1287,35 @@ mod tests {
 
         let videos = channel.get_latest_videos(1, client).await;
 
-        assert!(videos.is_err());
+        assert!(matches!(videos, Err(Error::NotFound)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn channel_is_serialized_with_snake_case_fields() {
+        let channel = Channel::new(
+            "RickAstleyYT".parse::<Handle>().unwrap(),
+            "Rick Astley".to_string(),
+            "UCuAXFkgsw1L7xaCfnd5JJOw".parse().unwrap(),
+        );
+
+        let json = serde_json::to_value(&channel).unwrap();
         assert_eq!(
-            videos.err().unwrap().msg,
-            "Failed to get videos from channel"
+            json,
+            serde_json::json!({
+                "handle": "rickastleyyt",
+                "title": "Rick Astley",
+                "channel_id": "UCuAXFkgsw1L7xaCfnd5JJOw"
+            })
         );
+
+        let deserialized: Channel = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized, channel);
+        assert!(serde_json::from_value::<Channel>(serde_json::json!({
+            "handle": "rick astley",
+            "title": "Rick Astley",
+            "channel_id": "UCuAXFkgsw1L7xaCfnd5JJOw"
+        }))
+        .is_err());
     }
 }
@@ -0,0 +1,307 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use tempfile::NamedTempFile;
+
+use super::id::{ChannelId, Handle};
+
+/// Mappings older than this are resolved again unless a store sets its own window
+pub(crate) const DEFAULT_MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+/// Minimum time between two writes of a `JsonFileChannelIdStore`
+const DEFAULT_WRITE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Channel a handle was resolved to, and when
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredChannelId {
+    pub channel_id: ChannelId,
+    pub title: String,
+    pub resolved_at: SystemTime,
+}
+
+impl StoredChannelId {
+    pub fn new(channel_id: ChannelId, title: String) -> Self {
+        StoredChannelId {
+            channel_id,
+            title,
+            resolved_at: SystemTime::now(),
+        }
+    }
+}
+
+/// Remembers the channel of each handle, resolving a handle is the most expensive lookup and
+/// handles almost never change
+pub trait ChannelIdStore: Send + Sync {
+    fn load(&self, handle: &Handle) -> io::Result<Option<StoredChannelId>>;
+    fn save(&self, handle: &Handle, channel: StoredChannelId) -> io::Result<()>;
+
+    /// How long a mapping is trusted before its handle is resolved again, 30 days by default
+    fn max_age(&self) -> Duration {
+        DEFAULT_MAX_AGE
+    }
+
+    /// Writes the mappings a store keeps aside, stores that save immediately do nothing
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Keeps the mappings for the lifetime of the process
+#[derive(Debug)]
+pub struct MemoryChannelIdStore {
+    channels: Mutex<HashMap<String, StoredChannelId>>,
+    max_age: Duration,
+}
+
+impl Default for MemoryChannelIdStore {
+    fn default() -> Self {
+        MemoryChannelIdStore {
+            channels: Mutex::new(HashMap::new()),
+            max_age: DEFAULT_MAX_AGE,
+        }
+    }
+}
+
+impl MemoryChannelIdStore {
+    pub fn new() -> Self {
+        MemoryChannelIdStore::default()
+    }
+
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+}
+
+impl ChannelIdStore for MemoryChannelIdStore {
+    fn load(&self, handle: &Handle) -> io::Result<Option<StoredChannelId>> {
+        Ok(self.channels.lock().unwrap().get(handle.as_str()).cloned())
+    }
+
+    fn save(&self, handle: &Handle, channel: StoredChannelId) -> io::Result<()> {
+        self.channels
+            .lock()
+            .unwrap()
+            .insert(handle.as_str().to_owned(), channel);
+        Ok(())
+    }
+
+    fn max_age(&self) -> Duration {
+        self.max_age
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonChannelEntry {
+    channel_id: String,
+    title: String,
+    resolved_at: SystemTime,
+}
+
+/// Keeps the mappings in a json file, read once when opened. Changes are written at most once
+/// per write interval, one second by default, and the remaining ones when the store is
+/// flushed or dropped
+#[derive(Debug)]
+pub struct JsonFileChannelIdStore {
+    path: PathBuf,
+    channels: Mutex<HashMap<String, StoredChannelId>>,
+    max_age: Duration,
+    write_interval: Duration,
+    /// Whether some changes are not written yet
+    pending: AtomicBool,
+    /// Held while writing the file, so the mappings stay readable during the write
+    last_write: Mutex<Option<Instant>>,
+}
+
+impl JsonFileChannelIdStore {
+    /// Reads the mappings of a file, a missing file is created on the first write
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let entries: HashMap<String, JsonChannelEntry> = match fs::read(&path) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => HashMap::new(),
+            content => serde_json::from_slice(&content?)?,
+        };
+
+        let channels = entries
+            .into_iter()
+            .map(|(handle, entry)| {
+                let channel_id = entry
+                    .channel_id
+                    .parse()
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                let channel = StoredChannelId {
+                    channel_id,
+                    title: entry.title,
+                    resolved_at: entry.resolved_at,
+                };
+                Ok((handle, channel))
+            })
+            .collect::<io::Result<_>>()?;
+
+        Ok(JsonFileChannelIdStore {
+            path,
+            channels: Mutex::new(channels),
+            max_age: DEFAULT_MAX_AGE,
+            write_interval: DEFAULT_WRITE_INTERVAL,
+            pending: AtomicBool::new(false),
+            last_write: Mutex::new(None),
+        })
+    }
+
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Minimum time between two writes, `Duration::ZERO` writes after every change
+    pub fn with_write_interval(mut self, write_interval: Duration) -> Self {
+        self.write_interval = write_interval;
+        self
+    }
+
+    /// Writes the pending changes, the caller holds the write lock
+    fn write_pending(&self, last_write: &mut Option<Instant>) -> io::Result<()> {
+        // Cleared before reading the mappings, changes saved meanwhile stay pending
+        if !self.pending.swap(false, Ordering::SeqCst) {
+            return Ok(());
+        }
+        let entries: HashMap<String, JsonChannelEntry> = self
+            .channels
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(handle, channel)| {
+                let entry = JsonChannelEntry {
+                    channel_id: channel.channel_id.to_string(),
+                    title: channel.title.clone(),
+                    resolved_at: channel.resolved_at,
+                };
+                (handle.clone(), entry)
+            })
+            .collect();
+
+        let result = self.write(&entries);
+        match result {
+            Ok(()) => *last_write = Some(Instant::now()),
+            Err(_) => self.pending.store(true, Ordering::SeqCst),
+        }
+        result
+    }
+
+    fn write(&self, entries: &HashMap<String, JsonChannelEntry>) -> io::Result<()> {
+        let directory = self
+            .path
+            .parent()
+            .filter(|path| !path.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        fs::create_dir_all(directory)?;
+        // Written aside first so a crash never leaves a truncated file behind, under a unique
+        // name so stores writing the same file do not overwrite each other's temporary file
+        let mut file = NamedTempFile::new_in(directory)?;
+        file.write_all(&serde_json::to_vec_pretty(entries)?)?;
+        file.persist(&self.path)?;
+        Ok(())
+    }
+}
+
+impl ChannelIdStore for JsonFileChannelIdStore {
+    fn load(&self, handle: &Handle) -> io::Result<Option<StoredChannelId>> {
+        Ok(self.channels.lock().unwrap().get(handle.as_str()).cloned())
+    }
+
+    fn save(&self, handle: &Handle, channel: StoredChannelId) -> io::Result<()> {
+        self.channels
+            .lock()
+            .unwrap()
+            .insert(handle.as_str().to_owned(), channel);
+        self.pending.store(true, Ordering::SeqCst);
+
+        // A write in progress or a recent one leaves the change to the next write
+        let Ok(mut last_write) = self.last_write.try_lock() else {
+            return Ok(());
+        };
+        if last_write.is_some_and(|time| time.elapsed() < self.write_interval) {
+            return Ok(());
+        }
+        self.write_pending(&mut last_write)
+    }
+
+    fn max_age(&self) -> Duration {
+        self.max_age
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        self.write_pending(&mut self.last_write.lock().unwrap())
+    }
+}
+
+impl Drop for JsonFileChannelIdStore {
+    fn drop(&mut self) {
+        // Nothing can report the error of a store being dropped
+        let _ = self.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_file_stores_are_reopened() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("channels.json");
+        let store = JsonFileChannelIdStore::open(&path).unwrap();
+        let channel = StoredChannelId::new(
+            "UCuAXFkgsw1L7xaCfnd5JJOw".parse().unwrap(),
+            "Rick Astley".to_string(),
+        );
+
+        store
+            .save(&"RickAstleyYT".parse().unwrap(), channel.clone())
+            .unwrap();
+        let reopened = JsonFileChannelIdStore::open(&path).unwrap();
+
+        assert_eq!(
+            reopened.load(&"rickastleyyt".parse().unwrap()).unwrap(),
+            Some(channel)
+        );
+        assert_eq!(reopened.load(&"someone".parse().unwrap()).unwrap(), None);
+    }
+
+    #[test]
+    fn json_file_writes_are_batched() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("channels");
+        let store = JsonFileChannelIdStore::open(&path)
+            .unwrap()
+            .with_write_interval(Duration::from_secs(3600));
+        let channel = |title: &str| {
+            StoredChannelId::new(
+                "UCuAXFkgsw1L7xaCfnd5JJOw".parse().unwrap(),
+                title.to_string(),
+            )
+        };
+        let handles: Vec<Handle> = ["first", "second", "third"]
+            .iter()
+            .map(|handle| handle.parse().unwrap())
+            .collect();
+
+        store.save(&handles[0], channel("First")).unwrap();
+        store.save(&handles[1], channel("Second")).unwrap();
+        let written = JsonFileChannelIdStore::open(&path).unwrap();
+        assert!(written.load(&handles[0]).unwrap().is_some());
+        assert_eq!(written.load(&handles[1]).unwrap(), None);
+
+        store.flush().unwrap();
+        store.save(&handles[2], channel("Third")).unwrap();
+        drop(store);
+        let reopened = JsonFileChannelIdStore::open(&path).unwrap();
+        assert!(handles
+            .iter()
+            .all(|handle| reopened.load(handle).unwrap().is_some()));
+    }
+}
@@ -0,0 +1,480 @@
+use crate::error::Error;
+use crate::utils::http_client::HttpClientTrait;
+use crate::utils::timestamp;
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use super::id::{ChannelId, VideoId};
+use super::page::Page;
+use super::requests::comment::retrieve_comment_replies;
+use super::video::Video;
+
+/// How the comment threads of a listing are sorted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CommentOrder {
+    /// Newest first
+    #[default]
+    Time,
+    Relevance,
+}
+
+impl CommentOrder {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CommentOrder::Time => "time",
+            CommentOrder::Relevance => "relevance",
+        }
+    }
+}
+
+/// Format of the text of the comments
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TextFormat {
+    /// Links, mentions and formatting as html tags
+    #[default]
+    Html,
+    PlainText,
+}
+
+impl TextFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TextFormat::Html => "html",
+            TextFormat::PlainText => "plainText",
+        }
+    }
+}
+
+/// Options used when listing comment threads
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommentThreadOptions {
+    pub order: CommentOrder,
+    /// Only returns the threads containing these terms
+    pub search_terms: Option<String>,
+    pub text_format: TextFormat,
+    /// Threads per page, between 1 and 100, 20 when `None`
+    pub max_results: Option<u32>,
+    /// Token of the page to get, from `Page::next_page_token`
+    pub page_token: Option<String>,
+}
+
+impl CommentThreadOptions {
+    pub fn with_order(mut self, order: CommentOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    pub fn with_search_terms(mut self, search_terms: &str) -> Self {
+        self.search_terms = Some(search_terms.to_owned());
+        self
+    }
+
+    pub fn with_text_format(mut self, text_format: TextFormat) -> Self {
+        self.text_format = text_format;
+        self
+    }
+
+    pub fn with_max_results(mut self, max_results: u32) -> Self {
+        self.max_results = Some(max_results.clamp(1, 100));
+        self
+    }
+
+    pub fn with_page_token(mut self, page_token: &str) -> Self {
+        self.page_token = Some(page_token.to_owned());
+        self
+    }
+}
+
+/// Options used when listing the replies of a comment
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommentReplyOptions {
+    pub text_format: TextFormat,
+    /// Replies per page, between 1 and 100, 20 when `None`
+    pub max_results: Option<u32>,
+    /// Token of the page to get, from `Page::next_page_token`
+    pub page_token: Option<String>,
+}
+
+impl CommentReplyOptions {
+    pub fn with_text_format(mut self, text_format: TextFormat) -> Self {
+        self.text_format = text_format;
+        self
+    }
+
+    pub fn with_max_results(mut self, max_results: u32) -> Self {
+        self.max_results = Some(max_results.clamp(1, 100));
+        self
+    }
+
+    pub fn with_page_token(mut self, page_token: &str) -> Self {
+        self.page_token = Some(page_token.to_owned());
+        self
+    }
+}
+
+/// A comment on a video, either at the top of a thread or a reply to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Comment {
+    pub id: String,
+    pub video_id: Option<VideoId>,
+    pub author_name: String,
+    /// `None` when the author has no channel, e.g. a deleted account
+    pub author_channel_id: Option<ChannelId>,
+    pub author_profile_image_url: Option<String>,
+    /// Text in the format of the options
+    pub text: String,
+    /// Text as written by the author, only returned to the author
+    pub text_original: Option<String>,
+    pub like_count: u64,
+    pub published_at: String,
+    /// Same as `published_at` when the comment was never edited
+    pub updated_at: String,
+    /// Total number of replies, always 0 for replies
+    pub reply_count: u64,
+    /// Id of the top level comment this comment replies to
+    pub parent_id: Option<String>,
+}
+
+impl Comment {
+    pub fn is_reply(&self) -> bool {
+        self.parent_id.is_some()
+    }
+
+    pub fn is_edited(&self) -> bool {
+        self.updated_at != self.published_at
+    }
+
+    /// Retrieves a page of the replies to this comment
+    pub async fn replies<T: HttpClientTrait>(
+        &self,
+        options: &CommentReplyOptions,
+        client: Arc<T>,
+    ) -> Result<Page<Comment>, Error> {
+        let mut page = retrieve_comment_replies(&self.id, options, &client).await?;
+        // Replies do not always repeat the video of their parent
+        for reply in &mut page.items {
+            reply.video_id = reply.video_id.take().or_else(|| self.video_id.clone());
+        }
+        Ok(page)
+    }
+}
+
+/// A top level comment along with the replies the API returned with it
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommentThread {
+    pub id: String,
+    pub video_id: Option<VideoId>,
+    /// Channel of the video
+    pub channel_id: Option<ChannelId>,
+    pub comment: Comment,
+    /// At most 5 replies, see `comment.reply_count` for the total
+    pub replies: Vec<Comment>,
+    pub can_reply: bool,
+    /// Format the text of the comment and its replies was requested in
+    pub text_format: TextFormat,
+}
+
+impl CommentThread {
+    /// Retrieves every reply of this thread in a text format, going through all their pages
+    /// unless the thread already came with all of them in that format
+    pub async fn with_all_replies<T: HttpClientTrait>(
+        &self,
+        text_format: TextFormat,
+        client: Arc<T>,
+    ) -> Result<CommentTree, Error> {
+        let complete = self.replies.len() as u64 >= self.comment.reply_count;
+        let mut replies = if complete && self.text_format == text_format {
+            self.replies.clone()
+        } else {
+            let mut replies = Vec::new();
+            let mut options = CommentReplyOptions::default()
+                .with_text_format(text_format)
+                .with_max_results(100);
+            loop {
+                let page = self.comment.replies(&options, client.clone()).await?;
+                replies.extend(page.items);
+                match page.next_page_token {
+                    Some(token) => options = options.with_page_token(&token),
+                    None => break,
+                }
+            }
+            replies
+        };
+        replies.sort_by(|a, b| timestamp::compare(&a.published_at, &b.published_at));
+
+        Ok(CommentTree {
+            video_id: self.video_id.clone(),
+            comment: self.comment.clone(),
+            replies,
+        })
+    }
+}
+
+/// A top level comment with every one of its replies, replies can not be replied to so the
+/// tree is never deeper than that
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommentTree {
+    /// Video the comments were posted on
+    pub video_id: Option<VideoId>,
+    pub comment: Comment,
+    /// Oldest first, each with the id of `comment` as `parent_id`
+    pub replies: Vec<Comment>,
+}
+
+impl CommentTree {
+    /// Retrieves the video the comments were posted on
+    pub async fn video<T: HttpClientTrait>(&self, client: Arc<T>) -> Result<Video, Error> {
+        let video_id = self
+            .video_id
+            .clone()
+            .ok_or_else(|| Error::InvalidInput("the comments have no video".to_owned()))?;
+
+        Video::search_video_by_id(video_id, client).await
+    }
+}
+
+/// Position of a feed of comment threads sorted by time, keep it between two polls to only get
+/// the threads posted in between
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommentCursor {
+    /// Publication time of the newest thread seen, `None` before the first poll
+    pub since: Option<String>,
+    /// Ids of the threads published exactly at `since`, so none of them is returned twice
+    pub seen_ids: Vec<String>,
+}
+
+impl CommentCursor {
+    /// Only gets the threads published after this RFC 3339 time, e.g. `2023-01-01T00:00:00Z`
+    /// or `2023-01-01T01:00:00.5+01:00`, which is kept in UTC as the API returns it
+    pub fn since(published_at: &str) -> Result<Self, Error> {
+        let since = timestamp::parse(published_at)
+            .and_then(timestamp::format)
+            .ok_or_else(|| {
+                Error::InvalidInput(format!("{:?} is not an RFC 3339 time", published_at))
+            })?;
+
+        Ok(CommentCursor {
+            since: Some(since),
+            seen_ids: Vec::new(),
+        })
+    }
+
+    pub(crate) fn is_new(&self, thread: &CommentThread) -> bool {
+        match self.since.as_deref() {
+            None => true,
+            Some(since) => match timestamp::compare(&thread.comment.published_at, since) {
+                Ordering::Greater => true,
+                Ordering::Equal => !self.seen_ids.contains(&thread.id),
+                Ordering::Less => false,
+            },
+        }
+    }
+
+    pub(crate) fn is_past(&self, thread: &CommentThread) -> bool {
+        self.since.as_deref().is_some_and(|since| {
+            timestamp::compare(&thread.comment.published_at, since) == Ordering::Less
+        })
+    }
+
+    /// Cursor after the threads of a poll
+    pub(crate) fn advance(&self, threads: &[CommentThread]) -> CommentCursor {
+        let newest = threads
+            .iter()
+            .map(|thread| thread.comment.published_at.as_str())
+            .chain(self.since.as_deref())
+            .max_by(|a, b| timestamp::compare(a, b));
+        let Some(newest) = newest else {
+            return self.clone();
+        };
+
+        let at_newest = |published_at: &str| timestamp::compare(published_at, newest).is_eq();
+        let mut seen_ids = if self.since.as_deref().is_some_and(at_newest) {
+            self.seen_ids.clone()
+        } else {
+            Vec::new()
+        };
+        seen_ids.extend(
+            threads
+                .iter()
+                .filter(|thread| at_newest(&thread.comment.published_at))
+                .map(|thread| thread.id.clone()),
+        );
+
+        CommentCursor {
+            since: Some(newest.to_owned()),
+            seen_ids,
+        }
+    }
+}
+
+/// Threads posted since a cursor, along with the cursor to poll from next time
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommentFeed {
+    /// Newest first
+    pub threads: Vec<CommentThread>,
+    pub cursor: CommentCursor,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::create_client_with_responses;
+
+    fn comment(id: &str, published_at: &str, parent_id: Option<&str>) -> Comment {
+        Comment {
+            id: id.to_string(),
+            video_id: None,
+            author_name: "@someone".to_string(),
+            author_channel_id: None,
+            author_profile_image_url: None,
+            text: "Never gonna give you up".to_string(),
+            text_original: None,
+            like_count: 0,
+            published_at: published_at.to_string(),
+            updated_at: published_at.to_string(),
+            reply_count: 0,
+            parent_id: parent_id.map(str::to_string),
+        }
+    }
+
+    fn thread(reply_count: u64, replies: Vec<Comment>) -> CommentThread {
+        CommentThread {
+            id: "UgzThread".to_string(),
+            video_id: Some(VideoId::new_unchecked("dQw4w9WgXcQ")),
+            channel_id: None,
+            comment: Comment {
+                video_id: Some(VideoId::new_unchecked("dQw4w9WgXcQ")),
+                reply_count,
+                ..comment("UgzThread", "2023-01-01T10:00:00Z", None)
+            },
+            replies,
+            can_reply: true,
+            text_format: TextFormat::Html,
+        }
+    }
+
+    fn reply_item(id: &str, published_at: &str) -> String {
+        format!(
+            r#"{{"id": "{}", "snippet": {{"parentId": "UgzThread", "publishedAt": "{}"}}}}"#,
+            id, published_at
+        )
+    }
+
+    #[tokio::test]
+    async fn every_page_of_replies_is_retrieved() {
+        let first_page = format!(
+            r#"{{"nextPageToken": "page2", "items": [{}, {}]}}"#,
+            reply_item("UgzThread.c", "2023-01-04T10:00:00Z"),
+            reply_item("UgzThread.a", "2023-01-02T10:00:00Z")
+        );
+        let second_page = format!(
+            r#"{{"items": [{}]}}"#,
+            reply_item("UgzThread.b", "2023-01-03T10:00:00Z")
+        );
+        let client = create_client_with_responses(vec![&second_page, &first_page]).await;
+        let inline = comment("UgzThread.c", "2023-01-04T10:00:00Z", Some("UgzThread"));
+
+        let tree = thread(3, vec![inline])
+            .with_all_replies(TextFormat::PlainText, client.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            client.requested_urls(),
+            vec![
+                "https://yt.lemnoslife.com/noKey/comments?part=snippet&parentId=UgzThread&textFormat=plainText&maxResults=100",
+                "https://yt.lemnoslife.com/noKey/comments?part=snippet&parentId=UgzThread&textFormat=plainText&maxResults=100&pageToken=page2"
+            ]
+        );
+        let ids: Vec<&str> = tree.replies.iter().map(|reply| reply.id.as_str()).collect();
+        assert_eq!(ids, vec!["UgzThread.a", "UgzThread.b", "UgzThread.c"]);
+        assert!(tree.replies.iter().all(|reply| reply.parent_id.as_deref()
+            == Some(tree.comment.id.as_str())
+            && reply.video_id == tree.video_id));
+        assert_eq!(tree.comment.id, "UgzThread");
+    }
+
+    #[tokio::test]
+    async fn inline_replies_are_used_when_complete() {
+        let client = create_client_with_responses(vec![]).await;
+        let replies = vec![
+            comment("UgzThread.b", "2023-01-03T10:00:00Z", Some("UgzThread")),
+            comment("UgzThread.a", "2023-01-02T10:00:00Z", Some("UgzThread")),
+        ];
+
+        let tree = thread(2, replies)
+            .with_all_replies(TextFormat::Html, client.clone())
+            .await
+            .unwrap();
+
+        assert!(client.requested_urls().is_empty());
+        assert_eq!(tree.replies[0].id, "UgzThread.a");
+        assert_eq!(tree.replies.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn inline_replies_are_retrieved_again_in_another_format() {
+        let response = format!(
+            r#"{{"items": [{}]}}"#,
+            reply_item("UgzThread.a", "2023-01-02T10:00:00Z")
+        );
+        let client = create_client_with_responses(vec![&response]).await;
+        let inline = comment("UgzThread.a", "2023-01-02T10:00:00Z", Some("UgzThread"));
+
+        let tree = thread(1, vec![inline])
+            .with_all_replies(TextFormat::PlainText, client.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(client.requested_urls().len(), 1);
+        assert_eq!(tree.replies.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn trees_without_a_video_are_invalid() {
+        let client = create_client_with_responses(vec![]).await;
+        let tree = CommentTree {
+            video_id: None,
+            comment: comment("UgzThread", "2023-01-01T10:00:00Z", None),
+            replies: Vec::new(),
+        };
+
+        assert!(matches!(
+            tree.video(client).await,
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn comment_threads_are_serialized_with_snake_case_fields() {
+        let thread = thread(
+            1,
+            vec![comment(
+                "UgzThread.reply",
+                "2023-01-02T10:00:00Z",
+                Some("UgzThread"),
+            )],
+        );
+
+        let json = serde_json::to_value(&thread).unwrap();
+        assert_eq!(json["video_id"], "dQw4w9WgXcQ");
+        assert_eq!(json["text_format"], "html");
+        assert_eq!(json["comment"]["reply_count"], 1);
+        assert_eq!(json["replies"][0]["parent_id"], "UgzThread");
+
+        let deserialized: CommentThread = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized, thread);
+    }
+}
@@ -0,0 +1,300 @@
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Reasons why a string is not a valid id or handle
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum IdError {
+    #[error("{kind} must be {expected} characters long, got {actual}")]
+    InvalidLength {
+        kind: &'static str,
+        expected: &'static str,
+        actual: usize,
+    },
+    #[error("{kind} contains the invalid character {character:?}")]
+    InvalidCharacter { kind: &'static str, character: char },
+    #[error("{kind} must start with {expected}")]
+    InvalidPrefix {
+        kind: &'static str,
+        expected: &'static str,
+    },
+}
+
+/// Prefixes of the playlist ids YouTube generates: user playlists, uploads, favorites,
+/// likes, albums, mixes and the special watch later and liked lists
+const PLAYLIST_PREFIXES: [&str; 8] = ["PL", "UU", "FL", "LL", "OL", "RD", "UL", "WL"];
+
+macro_rules! id_type {
+    ($(#[$meta:meta])* $name:ident, $validate:ident, $display:literal) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
+        pub struct $name(String);
+
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = IdError;
+
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                $validate(value.trim()).map(Self)
+            }
+        }
+
+        impl TryFrom<String> for $name {
+            type Error = IdError;
+
+            fn try_from(value: String) -> Result<Self, Self::Error> {
+                value.parse()
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, $display, self.0)
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+    };
+}
+
+id_type!(
+    /// Id of a video, 11 characters long, e.g. `dQw4w9WgXcQ`
+    VideoId,
+    validate_video_id,
+    "{}"
+);
+
+id_type!(
+    /// Id of a channel, 24 characters long and starting with `UC`, e.g. `UCuAXFkgsw1L7xaCfnd5JJOw`
+    ChannelId,
+    validate_channel_id,
+    "{}"
+);
+
+id_type!(
+    /// Id of a playlist, e.g. `PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI` or the uploads playlist
+    /// of a channel `UUuAXFkgsw1L7xaCfnd5JJOw`
+    PlaylistId,
+    validate_playlist_id,
+    "{}"
+);
+
+id_type!(
+    /// Handle of a channel, stored in lowercase as handles are case insensitive, and without
+    /// the leading `@` which is added when displayed
+    Handle,
+    validate_handle,
+    "@{}"
+);
+
+macro_rules! trusted_id {
+    ($($name:ident),*) => {
+        $(
+            impl $name {
+                /// Wraps an id returned by the API, which is trusted to be valid
+                pub(crate) fn new_unchecked(value: impl Into<String>) -> Self {
+                    Self(value.into())
+                }
+            }
+        )*
+    };
+}
+
+// Handles returned by the API are validated instead, as legacy custom urls are not handles
+trusted_id!(VideoId, ChannelId, PlaylistId);
+
+fn validate_charset(kind: &'static str, value: &str) -> Result<(), IdError> {
+    match value
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || *c == '_' || *c == '-'))
+    {
+        Some(character) => Err(IdError::InvalidCharacter { kind, character }),
+        None => Ok(()),
+    }
+}
+
+fn validate_video_id(value: &str) -> Result<String, IdError> {
+    if value.len() != 11 {
+        return Err(IdError::InvalidLength {
+            kind: "video id",
+            expected: "11",
+            actual: value.chars().count(),
+        });
+    }
+    validate_charset("video id", value)?;
+
+    Ok(value.to_owned())
+}
+
+fn validate_channel_id(value: &str) -> Result<String, IdError> {
+    if !value.starts_with("UC") {
+        return Err(IdError::InvalidPrefix {
+            kind: "channel id",
+            expected: "UC",
+        });
+    }
+    if value.len() != 24 {
+        return Err(IdError::InvalidLength {
+            kind: "channel id",
+            expected: "24",
+            actual: value.chars().count(),
+        });
+    }
+    validate_charset("channel id", value)?;
+
+    Ok(value.to_owned())
+}
+
+fn validate_playlist_id(value: &str) -> Result<String, IdError> {
+    if !PLAYLIST_PREFIXES
+        .iter()
+        .any(|prefix| value.starts_with(prefix))
+    {
+        return Err(IdError::InvalidPrefix {
+            kind: "playlist id",
+            expected: "PL, UU, FL, LL, OL, RD, UL or WL",
+        });
+    }
+    if value.len() > 64 {
+        return Err(IdError::InvalidLength {
+            kind: "playlist id",
+            expected: "at most 64",
+            actual: value.chars().count(),
+        });
+    }
+    validate_charset("playlist id", value)?;
+
+    Ok(value.to_owned())
+}
+
+fn validate_handle(value: &str) -> Result<String, IdError> {
+    let value = value.strip_prefix('@').unwrap_or(value);
+    let length = value.chars().count();
+    if !(3..=30).contains(&length) {
+        return Err(IdError::InvalidLength {
+            kind: "handle",
+            expected: "between 3 and 30",
+            actual: length,
+        });
+    }
+    if let Some(character) = value
+        .chars()
+        .find(|c| !(c.is_alphanumeric() || *c == '_' || *c == '-' || *c == '.'))
+    {
+        return Err(IdError::InvalidCharacter {
+            kind: "handle",
+            character,
+        });
+    }
+
+    Ok(value.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_ids_are_parsed() {
+        assert_eq!("dQw4w9WgXcQ".parse::<VideoId>().unwrap(), "dQw4w9WgXcQ");
+        assert_eq!(
+            "UCuAXFkgsw1L7xaCfnd5JJOw".parse::<ChannelId>().unwrap(),
+            "UCuAXFkgsw1L7xaCfnd5JJOw"
+        );
+        assert_eq!(
+            "UUuAXFkgsw1L7xaCfnd5JJOw".parse::<PlaylistId>().unwrap(),
+            "UUuAXFkgsw1L7xaCfnd5JJOw"
+        );
+        assert_eq!(
+            "PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI"
+                .parse::<PlaylistId>()
+                .unwrap(),
+            "PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI"
+        );
+    }
+
+    #[test]
+    fn invalid_ids_are_rejected() {
+        assert!(matches!(
+            "dQw4w9WgXc".parse::<VideoId>(),
+            Err(IdError::InvalidLength { actual: 10, .. })
+        ));
+        assert!(matches!(
+            "dQw4w9WgX!Q".parse::<VideoId>(),
+            Err(IdError::InvalidCharacter { character: '!', .. })
+        ));
+        assert!(matches!(
+            "@rickastleyyt".parse::<ChannelId>(),
+            Err(IdError::InvalidPrefix { .. })
+        ));
+        assert!(matches!(
+            "UCuAXFkgsw1L7xaCfnd5JJ".parse::<ChannelId>(),
+            Err(IdError::InvalidLength { .. })
+        ));
+        assert!(matches!(
+            "XXFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI".parse::<PlaylistId>(),
+            Err(IdError::InvalidPrefix { .. })
+        ));
+    }
+
+    #[test]
+    fn handles_are_normalized() {
+        let handle = "@rickastleyyt".parse::<Handle>().unwrap();
+
+        assert_eq!(handle, "rickastleyyt".parse::<Handle>().unwrap());
+        assert_eq!(handle, "@RickAstleyYT".parse::<Handle>().unwrap());
+        assert_eq!(handle.as_str(), "rickastleyyt");
+        assert_eq!(handle.to_string(), "@rickastleyyt");
+        assert!("@ab".parse::<Handle>().is_err());
+        assert!("rick astley".parse::<Handle>().is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn invalid_ids_are_not_deserialized() {
+        assert!(serde_json::from_str::<VideoId>(r#""dQw4w9WgXc""#).is_err());
+        assert!(serde_json::from_str::<ChannelId>(r#""@rickastleyyt""#).is_err());
+        assert!(
+            serde_json::from_str::<PlaylistId>(r#""XXFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI""#).is_err()
+        );
+        assert!(serde_json::from_str::<Handle>(r#""rick astley""#).is_err());
+        assert_eq!(
+            serde_json::from_str::<Handle>(r#""@RickAstleyYT""#).unwrap(),
+            "rickastleyyt"
+        );
+        assert_eq!(
+            serde_json::to_string(&"UCuAXFkgsw1L7xaCfnd5JJOw".parse::<ChannelId>().unwrap())
+                .unwrap(),
+            r#""UCuAXFkgsw1L7xaCfnd5JJOw""#
+        );
+    }
+}
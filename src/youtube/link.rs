@@ -0,0 +1,501 @@
+use crate::error::Error;
+use crate::utils::http_client::HttpClientTrait;
+use std::str::FromStr;
+use std::sync::Arc;
+use thiserror::Error;
+use url::Url;
+
+use super::channel::Channel;
+use super::id::{ChannelId, Handle, IdError, PlaylistId, VideoId};
+use super::listing::ListingOptions;
+use super::requests::video::retrieve_latest_videos;
+use super::video::Video;
+
+/// Maximum number of videos returned when resolving a playlist url
+const PLAYLIST_VIDEOS: i32 = 50;
+
+/// Any link to a video, channel or playlist on youtube.com, m.youtube.com,
+/// music.youtube.com or youtu.be
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "value", rename_all = "snake_case")
+)]
+pub enum YoutubeUrl {
+    Video {
+        id: VideoId,
+        kind: VideoUrlKind,
+        /// Second where the playback starts, from the `t` or `start` parameter
+        start: Option<u32>,
+        /// Playlist the video is being played from, from the `list` parameter
+        playlist: Option<PlaylistId>,
+        /// Position of the video in the playlist, from the `index` parameter
+        index: Option<u32>,
+    },
+    /// https://www.youtube.com/@{handle}
+    Handle(Handle),
+    /// https://www.youtube.com/channel/{channel_id}
+    Channel(ChannelId),
+    /// Legacy custom url, https://www.youtube.com/c/{name}
+    CustomUrl(String),
+    /// Legacy username url, https://www.youtube.com/user/{name}
+    User(String),
+    /// https://www.youtube.com/playlist?list={playlist_id}
+    Playlist(PlaylistId),
+}
+
+/// Path a video url was built with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum VideoUrlKind {
+    /// /watch?v={id}
+    Watch,
+    /// youtu.be/{id}
+    ShortLink,
+    /// /shorts/{id}
+    Short,
+    /// /embed/{id}
+    Embed,
+    /// /live/{id}
+    Live,
+}
+
+/// What a `YoutubeUrl` points to
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "value", rename_all = "snake_case")
+)]
+pub enum Resolved {
+    Video(Box<Video>),
+    Channel(Channel),
+    /// Latest videos of the playlist
+    Playlist(Vec<Video>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum UrlError {
+    #[error("Not a valid url: {0}")]
+    Malformed(String),
+    #[error("Not a youtube url: {0}")]
+    NotYoutube(String),
+    #[error("Youtube url not supported: {0}")]
+    Unsupported(String),
+    #[error(transparent)]
+    InvalidId(#[from] IdError),
+}
+
+impl YoutubeUrl {
+    /// Classifies a youtube url, the scheme can be omitted
+    pub fn parse(input: &str) -> Result<Self, UrlError> {
+        let input = input.trim();
+        let url = match Url::parse(input) {
+            Ok(url) => url,
+            Err(_) => Url::parse(&format!("https://{}", input))
+                .map_err(|_| UrlError::Malformed(input.to_owned()))?,
+        };
+
+        let host = url
+            .host_str()
+            .ok_or_else(|| UrlError::Malformed(input.to_owned()))?
+            .to_ascii_lowercase();
+        let host = host.strip_prefix("www.").unwrap_or(&host);
+        let segments: Vec<&str> = url
+            .path_segments()
+            .map(|segments| segments.filter(|segment| !segment.is_empty()).collect())
+            .unwrap_or_default();
+        let query = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+        };
+
+        let video = |id: &str, kind: VideoUrlKind| -> Result<Self, UrlError> {
+            Ok(YoutubeUrl::Video {
+                id: id.parse()?,
+                kind,
+                start: query("t")
+                    .or_else(|| query("start"))
+                    .and_then(|time| parse_timestamp(&time)),
+                // Temporary lists have ids that are not playlists, they do not make the
+                // video url invalid
+                playlist: query("list").and_then(|list| list.parse().ok()),
+                index: query("index").and_then(|index| index.parse().ok()),
+            })
+        };
+
+        match host {
+            "youtu.be" => match segments.as_slice() {
+                [id] => video(id, VideoUrlKind::ShortLink),
+                _ => Err(UrlError::Unsupported(input.to_owned())),
+            },
+            "youtube.com" | "m.youtube.com" | "music.youtube.com" | "youtube-nocookie.com" => {
+                match segments.as_slice() {
+                    ["watch"] => match query("v") {
+                        Some(id) => video(&id, VideoUrlKind::Watch),
+                        None => Err(UrlError::Unsupported(input.to_owned())),
+                    },
+                    ["shorts", id] => video(id, VideoUrlKind::Short),
+                    ["embed", id] => video(id, VideoUrlKind::Embed),
+                    ["live", id] => video(id, VideoUrlKind::Live),
+                    ["playlist"] => match query("list") {
+                        Some(list) => Ok(YoutubeUrl::Playlist(list.parse()?)),
+                        None => Err(UrlError::Unsupported(input.to_owned())),
+                    },
+                    ["channel", id, ..] => Ok(YoutubeUrl::Channel(id.parse()?)),
+                    ["c", name, ..] => Ok(YoutubeUrl::CustomUrl(decode(name))),
+                    ["user", name, ..] => Ok(YoutubeUrl::User(decode(name))),
+                    [handle, ..] if handle.starts_with('@') => {
+                        Ok(YoutubeUrl::Handle(decode(handle).parse()?))
+                    }
+                    _ => Err(UrlError::Unsupported(input.to_owned())),
+                }
+            }
+            _ => Err(UrlError::NotYoutube(input.to_owned())),
+        }
+    }
+
+    /// Retrieves the video, channel or playlist videos this url points to. Legacy custom urls
+    /// fail with `Error::InvalidInput`, the API cannot look them up
+    pub async fn resolve<T: HttpClientTrait>(&self, client: Arc<T>) -> Result<Resolved, Error> {
+        match self {
+            YoutubeUrl::Video { id, .. } => Video::search_video_by_id(id.clone(), client)
+                .await
+                .map(|video| Resolved::Video(Box::new(video))),
+            YoutubeUrl::Handle(handle) => Channel::initialize(handle.clone(), client)
+                .await
+                .map(Resolved::Channel),
+            YoutubeUrl::Channel(channel_id) => Channel::from_id(channel_id.clone(), client)
+                .await
+                .map(Resolved::Channel),
+            // Custom names are not handles, the channel owning the same handle may be another one
+            YoutubeUrl::CustomUrl(name) => Err(Error::InvalidInput(format!(
+                "the legacy custom url /c/{} cannot be resolved",
+                name
+            ))),
+            YoutubeUrl::User(username) => Channel::from_username(username, client)
+                .await
+                .map(Resolved::Channel),
+            YoutubeUrl::Playlist(playlist_id) => retrieve_latest_videos(
+                playlist_id.as_str(),
+                PLAYLIST_VIDEOS,
+                &ListingOptions::default(),
+                client,
+            )
+            .await
+            .map(|listing| Resolved::Playlist(listing.videos))
+            .map_err(Error::from),
+        }
+    }
+}
+
+impl FromStr for YoutubeUrl {
+    type Err = UrlError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        YoutubeUrl::parse(value)
+    }
+}
+
+/// Handles and names with non ascii characters are percent encoded in urls
+fn decode(segment: &str) -> String {
+    percent_encoding::percent_decode_str(segment)
+        .decode_utf8_lossy()
+        .into_owned()
+}
+
+/// Parses the `t` parameter, either in seconds (`90`, `90s`) or in `1h2m3s` format
+fn parse_timestamp(value: &str) -> Option<u32> {
+    if let Ok(seconds) = value.parse() {
+        return Some(seconds);
+    }
+
+    let mut total: u32 = 0;
+    let mut number = String::new();
+    for character in value.chars() {
+        match character {
+            '0'..='9' => number.push(character),
+            'h' | 'm' | 's' => {
+                let multiplier = match character {
+                    'h' => 3600,
+                    'm' => 60,
+                    _ => 1,
+                };
+                let seconds = number.parse::<u32>().ok()?.checked_mul(multiplier)?;
+                total = total.checked_add(seconds)?;
+                number.clear();
+            }
+            _ => return None,
+        }
+    }
+
+    if number.is_empty() && !value.is_empty() {
+        Some(total)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::create_client_with_responses;
+
+    fn video_id() -> VideoId {
+        "dQw4w9WgXcQ".parse().unwrap()
+    }
+
+    #[test]
+    fn video_urls_are_parsed() {
+        let urls = [
+            (
+                "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+                VideoUrlKind::Watch,
+            ),
+            (
+                "https://m.youtube.com/watch?v=dQw4w9WgXcQ",
+                VideoUrlKind::Watch,
+            ),
+            (
+                "https://music.youtube.com/watch?v=dQw4w9WgXcQ",
+                VideoUrlKind::Watch,
+            ),
+            ("youtube.com/watch?v=dQw4w9WgXcQ", VideoUrlKind::Watch),
+            ("https://youtu.be/dQw4w9WgXcQ", VideoUrlKind::ShortLink),
+            (
+                "https://www.youtube.com/shorts/dQw4w9WgXcQ",
+                VideoUrlKind::Short,
+            ),
+            (
+                "https://www.youtube.com/embed/dQw4w9WgXcQ",
+                VideoUrlKind::Embed,
+            ),
+            (
+                "https://www.youtube.com/live/dQw4w9WgXcQ",
+                VideoUrlKind::Live,
+            ),
+        ];
+
+        for (url, kind) in urls {
+            assert_eq!(
+                YoutubeUrl::parse(url).unwrap(),
+                YoutubeUrl::Video {
+                    id: video_id(),
+                    kind,
+                    start: None,
+                    playlist: None,
+                    index: None,
+                },
+                "{}",
+                url
+            );
+        }
+    }
+
+    #[test]
+    fn video_url_parameters_are_captured() {
+        let url = YoutubeUrl::parse(
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ&list=PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI&index=3&t=1m30s",
+        )
+        .unwrap();
+
+        assert_eq!(
+            url,
+            YoutubeUrl::Video {
+                id: video_id(),
+                kind: VideoUrlKind::Watch,
+                start: Some(90),
+                playlist: Some("PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI".parse().unwrap()),
+                index: Some(3),
+            }
+        );
+        assert_eq!(parse_timestamp("43"), Some(43));
+        assert_eq!(parse_timestamp("1h2s"), Some(3602));
+        assert_eq!(parse_timestamp("1x"), None);
+        assert_eq!(parse_timestamp(""), None);
+        assert_eq!(
+            YoutubeUrl::parse("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=").unwrap(),
+            YoutubeUrl::Video {
+                id: video_id(),
+                kind: VideoUrlKind::Watch,
+                start: None,
+                playlist: None,
+                index: None,
+            }
+        );
+    }
+
+    #[test]
+    fn overflowing_timestamps_are_ignored() {
+        assert_eq!(parse_timestamp("2000000h"), None);
+        assert_eq!(parse_timestamp("1193046h28m15s"), Some(u32::MAX));
+        assert_eq!(parse_timestamp("1193046h28m16s"), None);
+        assert_eq!(
+            YoutubeUrl::parse("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=2000000h").unwrap(),
+            YoutubeUrl::Video {
+                id: video_id(),
+                kind: VideoUrlKind::Watch,
+                start: None,
+                playlist: None,
+                index: None,
+            }
+        );
+    }
+
+    #[test]
+    fn video_urls_keep_lists_that_are_not_playlists() {
+        for list in ["TLPQMjkwNzIwMjO", "TLGGdQw4w9WgXcQ"] {
+            let url = format!("https://www.youtube.com/watch?v=dQw4w9WgXcQ&list={}", list);
+
+            assert!(matches!(
+                YoutubeUrl::parse(&url).unwrap(),
+                YoutubeUrl::Video { playlist: None, .. }
+            ));
+        }
+    }
+
+    #[test]
+    fn path_segments_are_percent_decoded() {
+        assert_eq!(decode("%E3%81%82+b"), "\u{3042}+b");
+        assert_eq!(
+            YoutubeUrl::parse("https://www.youtube.com/c/%E3%81%82").unwrap(),
+            YoutubeUrl::CustomUrl("\u{3042}".to_owned())
+        );
+        assert_eq!(
+            YoutubeUrl::parse("https://www.youtube.com/user/%E3%81%82").unwrap(),
+            YoutubeUrl::User("\u{3042}".to_owned())
+        );
+    }
+
+    #[test]
+    fn channel_and_playlist_urls_are_parsed() {
+        assert_eq!(
+            YoutubeUrl::parse("https://www.youtube.com/@rickastleyyt/videos").unwrap(),
+            YoutubeUrl::Handle("rickastleyyt".parse().unwrap())
+        );
+        assert_eq!(
+            YoutubeUrl::parse("https://www.youtube.com/channel/UCuAXFkgsw1L7xaCfnd5JJOw").unwrap(),
+            YoutubeUrl::Channel("UCuAXFkgsw1L7xaCfnd5JJOw".parse().unwrap())
+        );
+        assert_eq!(
+            YoutubeUrl::parse("https://www.youtube.com/c/RickAstleyVEVO").unwrap(),
+            YoutubeUrl::CustomUrl("RickAstleyVEVO".to_owned())
+        );
+        assert_eq!(
+            YoutubeUrl::parse("https://www.youtube.com/user/RickAstleyVEVO").unwrap(),
+            YoutubeUrl::User("RickAstleyVEVO".to_owned())
+        );
+        assert_eq!(
+            YoutubeUrl::parse(
+                "https://www.youtube.com/playlist?list=PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI"
+            )
+            .unwrap(),
+            YoutubeUrl::Playlist("PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn invalid_urls_are_rejected() {
+        assert!(matches!(
+            YoutubeUrl::parse("https://vimeo.com/123"),
+            Err(UrlError::NotYoutube(_))
+        ));
+        assert!(matches!(
+            YoutubeUrl::parse("https://www.youtube.com/feed/trending"),
+            Err(UrlError::Unsupported(_))
+        ));
+        assert!(matches!(
+            YoutubeUrl::parse("https://youtu.be/short"),
+            Err(UrlError::InvalidId(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn video_urls_are_resolved() {
+        let client = create_client_with_responses(vec![
+            r#"{
+                "items": [
+                    {
+                        "snippet": {
+                            "publishedAt": "2009-10-25T06:57:33Z",
+                            "title": "Video Title",
+                            "description": "Video Description"
+                        }
+                    }
+                ]
+            }"#,
+        ])
+        .await;
+
+        let resolved = YoutubeUrl::parse("https://youtu.be/dQw4w9WgXcQ?t=43")
+            .unwrap()
+            .resolve(client)
+            .await
+            .unwrap();
+
+        match resolved {
+            Resolved::Video(video) => assert_eq!(video.title.as_deref(), Some("Video Title")),
+            _ => panic!("Expected a video"),
+        }
+    }
+
+    #[tokio::test]
+    async fn custom_urls_are_not_resolved() {
+        let client = create_client_with_responses(vec![]).await;
+
+        let resolved = YoutubeUrl::CustomUrl("RickAstleyVEVO".to_owned())
+            .resolve(client.clone())
+            .await;
+
+        assert!(matches!(resolved, Err(Error::InvalidInput(_))));
+        assert!(client.requested_urls().is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn urls_are_serialized_as_tagged_values() {
+        let handle = YoutubeUrl::Handle("rickastleyyt".parse().unwrap());
+        let video = YoutubeUrl::Video {
+            id: video_id(),
+            kind: VideoUrlKind::ShortLink,
+            start: Some(43),
+            playlist: None,
+            index: None,
+        };
+
+        let json = serde_json::to_value(&handle).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"type": "handle", "value": "rickastleyyt"})
+        );
+        assert_eq!(serde_json::from_value::<YoutubeUrl>(json).unwrap(), handle);
+        let json = serde_json::to_value(&video).unwrap();
+        assert_eq!(json["type"], "video");
+        assert_eq!(json["value"]["kind"], "short_link");
+        assert_eq!(serde_json::from_value::<YoutubeUrl>(json).unwrap(), video);
+        assert!(serde_json::from_value::<YoutubeUrl>(
+            serde_json::json!({"type": "channel", "value": "rickastleyyt"})
+        )
+        .is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn resolved_values_are_serialized_as_tagged_values() {
+        let resolved = Resolved::Channel(Channel {
+            handle: Some("rickastleyyt".parse().unwrap()),
+            title: "Rick Astley".to_string(),
+            channel_id: "UCuAXFkgsw1L7xaCfnd5JJOw".parse().unwrap(),
+        });
+
+        let json = serde_json::to_value(&resolved).unwrap();
+        assert_eq!(json["type"], "channel");
+        assert_eq!(json["value"]["channel_id"], "UCuAXFkgsw1L7xaCfnd5JJOw");
+        assert_eq!(serde_json::from_value::<Resolved>(json).unwrap(), resolved);
+        let json = serde_json::to_value(Resolved::Playlist(Vec::new())).unwrap();
+        assert_eq!(json, serde_json::json!({"type": "playlist", "value": []}));
+    }
+}
@@ -0,0 +1,88 @@
+use thiserror::Error;
+
+use super::id::VideoId;
+use super::video::{Availability, Video};
+
+/// How items that cannot be turned into a video are handled when listing videos
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ParseMode {
+    /// Invalid items are skipped and reported in `VideoListing::skipped`
+    #[default]
+    Lenient,
+    /// The first invalid item fails the whole listing with `Error::Decode`
+    Strict,
+}
+
+/// What to do with the private, deleted and unavailable videos of a listing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum UnavailablePolicy {
+    /// Return them as videos, check `Video::availability` to tell them apart
+    #[default]
+    Include,
+    /// Drop them silently
+    Exclude,
+    /// Report them in `VideoListing::skipped`
+    Report,
+}
+
+/// Options used when listing the videos of a channel or playlist
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ListingOptions {
+    pub mode: ParseMode,
+    pub unavailable: UnavailablePolicy,
+}
+
+impl ListingOptions {
+    pub fn strict() -> Self {
+        Self {
+            mode: ParseMode::Strict,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_unavailable(mut self, unavailable: UnavailablePolicy) -> Self {
+        self.unavailable = unavailable;
+        self
+    }
+}
+
+/// Videos of a listing, along with the items that were skipped
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VideoListing {
+    pub videos: Vec<Video>,
+    pub skipped: Vec<ItemError>,
+}
+
+/// An item of a listing that could not be turned into a video
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[error("Item {index} skipped: {kind}")]
+pub struct ItemError {
+    /// Position of the item in the response
+    pub index: usize,
+    /// Id of the video, when the item has one
+    pub video_id: Option<VideoId>,
+    pub kind: ItemErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[non_exhaustive]
+pub enum ItemErrorKind {
+    /// A field of the item does not have the expected format
+    #[error("invalid field `{path}`: {message}")]
+    Malformed { path: String, message: String },
+    /// The item does not reference any video
+    #[error("missing video id")]
+    MissingVideoId,
+    /// The video is private, deleted or unavailable
+    #[error("video is {0}")]
+    Unavailable(Availability),
+}
@@ -1,4 +1,14 @@
+pub mod batch;
+pub mod bulk;
 pub mod channel;
+pub mod channel_id_store;
+pub mod comment;
+pub mod id;
+pub mod link;
+pub mod listing;
+pub mod page;
+pub mod parts;
 pub mod playlist;
 mod requests;
+pub mod thumbnail;
 pub mod video;
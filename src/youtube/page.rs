@@ -0,0 +1,21 @@
+use super::batch::SkippedItem;
+
+/// One page of a paginated listing, pass `next_page_token` back in the options to get the
+/// next one
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// `None` on the last page
+    pub next_page_token: Option<String>,
+    /// Estimate of the number of items of the whole listing, when the API reports it
+    pub total_results: Option<u64>,
+    /// Items of this page that could not be decoded, with the id they were returned with
+    pub skipped: Vec<SkippedItem<String>>,
+}
+
+impl<T> Page<T> {
+    pub fn has_next_page(&self) -> bool {
+        self.next_page_token.is_some()
+    }
+}
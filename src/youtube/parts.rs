@@ -0,0 +1,137 @@
+use std::collections::HashSet;
+
+/// Section of a resource the API can return, each one costs bandwidth and parsing time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[non_exhaustive]
+pub enum Part {
+    Snippet,
+    Statistics,
+    ContentDetails,
+    Status,
+    LiveStreamingDetails,
+    BrandingSettings,
+    TopicDetails,
+}
+
+impl Part {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Part::Snippet => "snippet",
+            Part::Statistics => "statistics",
+            Part::ContentDetails => "contentDetails",
+            Part::Status => "status",
+            Part::LiveStreamingDetails => "liveStreamingDetails",
+            Part::BrandingSettings => "brandingSettings",
+            Part::TopicDetails => "topicDetails",
+        }
+    }
+}
+
+/// Parts a video lookup requests unless told otherwise
+pub(crate) const DEFAULT_VIDEO_PARTS: &[Part] = &[
+    Part::Snippet,
+    Part::LiveStreamingDetails,
+    Part::Status,
+    Part::ContentDetails,
+];
+
+/// Parts a channel details lookup requests unless told otherwise
+pub(crate) const DEFAULT_CHANNEL_PARTS: &[Part] = &[
+    Part::Snippet,
+    Part::Statistics,
+    Part::BrandingSettings,
+    Part::TopicDetails,
+    Part::Status,
+];
+
+/// Which sections of a resource are requested, the sections that are not requested are
+/// left as `None` on the returned model
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RequestOptions {
+    /// Parts to request, the default parts of the lookup when empty
+    pub parts: Vec<Part>,
+    /// Projection sent as `fields=`, e.g. `items(id,snippet(title,publishedAt))`. Batch
+    /// lookups always add `items/id` to it to match the items with their ids
+    pub fields: Option<String>,
+}
+
+impl RequestOptions {
+    pub fn with_parts(parts: &[Part]) -> Self {
+        RequestOptions {
+            parts: parts.to_vec(),
+            fields: None,
+        }
+    }
+
+    pub fn with_part(mut self, part: Part) -> Self {
+        self.parts.push(part);
+        self
+    }
+
+    pub fn with_fields(mut self, fields: &str) -> Self {
+        self.fields = Some(fields.to_owned());
+        self
+    }
+
+    /// Query parameters of these options, with the default parts of the lookup
+    pub(crate) fn query(&self, default_parts: &[Part]) -> Vec<(&'static str, String)> {
+        let mut parts = if self.parts.is_empty() {
+            default_parts.to_vec()
+        } else {
+            self.parts.clone()
+        };
+        let mut seen = HashSet::new();
+        parts.retain(|part| seen.insert(*part));
+
+        let parts: Vec<&str> = parts.iter().map(Part::as_str).collect();
+        let mut query = vec![("part", parts.join(","))];
+        if let Some(fields) = &self.fields {
+            query.push(("fields", fields.clone()));
+        }
+        query
+    }
+
+    /// Query parameters of a lookup of many ids, the projection keeps the id of every item
+    pub(crate) fn batch_query(&self, default_parts: &[Part]) -> Vec<(&'static str, String)> {
+        let mut query = self.query(default_parts);
+        for (name, value) in &mut query {
+            if *name == "fields" {
+                value.push_str(",items/id");
+            }
+        }
+        query
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_requests_each_part_once() {
+        let options = RequestOptions::with_parts(&[Part::Statistics, Part::Statistics])
+            .with_fields("items(statistics)");
+
+        assert_eq!(
+            options.query(DEFAULT_VIDEO_PARTS),
+            vec![
+                ("part", "statistics".to_owned()),
+                ("fields", "items(statistics)".to_owned())
+            ]
+        );
+        assert_eq!(
+            options.batch_query(DEFAULT_VIDEO_PARTS)[1],
+            ("fields", "items(statistics),items/id".to_owned())
+        );
+        assert_eq!(
+            RequestOptions::default().query(DEFAULT_VIDEO_PARTS),
+            vec![(
+                "part",
+                "snippet,liveStreamingDetails,status,contentDetails".to_owned()
+            )]
+        );
+    }
+}
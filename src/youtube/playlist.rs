@@ -1,24 +1,106 @@
-use thiserror::Error;
+use crate::error::Error;
+use crate::utils::http_client::HttpClientTrait;
+use std::sync::Arc;
+
+use super::batch::{self, BatchLookup};
+use super::id::{ChannelId, PlaylistId};
+use super::requests::playlist::retrieve_playlists_by_ids;
 
 /// Main playlist for a channel, all its videos will be uploaded to this playlist
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Playlist {
-    pub channel_id: String,
-    pub playlist_id: String,
-}
-
-#[derive(Debug, Error)]
-#[error("{msg}")]
-pub struct PlaylistError {
-    source: Option<anyhow::Error>,
-    msg: String,
+    pub channel_id: ChannelId,
+    pub playlist_id: PlaylistId,
 }
 
 impl Playlist {
-    pub fn new(channel_id: String, playlist_id: String) -> Self {
+    pub fn new(channel_id: ChannelId, playlist_id: PlaylistId) -> Self {
         Self {
             channel_id,
             playlist_id,
         }
     }
+
+    /// Retrieves many playlists at once, 50 per request, along with the ids that do not exist
+    pub async fn from_ids<T: HttpClientTrait>(
+        playlist_ids: &[PlaylistId],
+        client: Arc<T>,
+    ) -> Result<BatchLookup<PlaylistId, Self>, Error> {
+        let fetch = |chunk: Vec<PlaylistId>| {
+            let client = client.clone();
+            async move {
+                let ids: Vec<&str> = chunk.iter().map(PlaylistId::as_str).collect();
+                let (playlists, skipped) = retrieve_playlists_by_ids(&ids, &client).await?;
+
+                Ok((
+                    playlists
+                        .into_iter()
+                        .map(|(playlist_id, channel_id)| Self::new(channel_id, playlist_id))
+                        .collect(),
+                    skipped,
+                ))
+            }
+        };
+
+        batch::lookup(playlist_ids, fetch, |playlist| &playlist.playlist_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::create_client_with_responses;
+
+    #[tokio::test]
+    async fn playlists_are_retrieved_by_many_ids() {
+        let client = create_client_with_responses(vec![
+            r#"{"items": [
+                {
+                    "id": "PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI",
+                    "snippet": {"channelId": "UCuAXFkgsw1L7xaCfnd5JJOw"}
+                }
+            ]}"#,
+        ])
+        .await;
+        let ids: Vec<PlaylistId> = [
+            "PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI",
+            "PLaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        ]
+        .iter()
+        .map(|id| id.parse().unwrap())
+        .collect();
+
+        let batch = Playlist::from_ids(&ids, client).await.unwrap();
+
+        assert_eq!(
+            batch.found,
+            vec![Playlist::new(
+                "UCuAXFkgsw1L7xaCfnd5JJOw".parse().unwrap(),
+                ids[0].clone()
+            )]
+        );
+        assert_eq!(batch.not_found, vec![ids[1].clone()]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn playlist_is_serialized_with_snake_case_fields() {
+        let playlist = Playlist::new(
+            "UCuAXFkgsw1L7xaCfnd5JJOw".parse().unwrap(),
+            "UUuAXFkgsw1L7xaCfnd5JJOw".parse().unwrap(),
+        );
+
+        let json = serde_json::to_value(&playlist).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "channel_id": "UCuAXFkgsw1L7xaCfnd5JJOw",
+                "playlist_id": "UUuAXFkgsw1L7xaCfnd5JJOw"
+            })
+        );
+
+        let deserialized: Playlist = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized, playlist);
+    }
 }
@@ -1,5 +1,9 @@
-use super::utils::{process_response, RequestError};
+use super::utils::{process_batch_response, process_response, RequestError};
 use crate::utils::http_client::HttpClientTrait;
+use crate::youtube::batch::SkippedItem;
+use crate::youtube::channel::ChannelDetails;
+use crate::youtube::id::{ChannelId, Handle};
+use crate::youtube::parts::{RequestOptions, DEFAULT_CHANNEL_PARTS};
 use serde::Deserialize;
 use std::sync::Arc;
 use url::Url;
@@ -22,6 +26,171 @@ struct ChannelSnippetReturn {
     channel_handle: String,
 }
 
+#[derive(Deserialize)]
+struct ChannelByIdReturn {
+    items: Vec<ChannelByIdItemReturn>,
+}
+
+#[derive(Deserialize)]
+struct ChannelByIdItemReturn {
+    snippet: ChannelByIdSnippetReturn,
+}
+
+impl ChannelByIdSnippetReturn {
+    /// Handle of the channel, legacy custom urls that are not valid handles are left out
+    fn handle(&self) -> Option<Handle> {
+        self.custom_url
+            .as_deref()
+            .and_then(|custom_url| custom_url.parse().ok())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChannelByIdSnippetReturn {
+    title: String,
+    custom_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ChannelIdReturn {
+    #[serde(default)]
+    items: Vec<ChannelIdItemReturn>,
+}
+
+#[derive(Deserialize)]
+struct ChannelIdItemReturn {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct ChannelDetailsReturn {
+    items: Vec<ChannelDetailsItemReturn>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChannelDetailsItemReturn {
+    #[serde(default)]
+    snippet: ChannelDetailsSnippetReturn,
+    statistics: Option<ChannelStatisticsReturn>,
+    branding_settings: Option<ChannelBrandingReturn>,
+    topic_details: Option<ChannelTopicDetailsReturn>,
+    status: Option<ChannelStatusReturn>,
+}
+
+// Every field is optional so the parts and fields of the options can leave them out
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChannelDetailsSnippetReturn {
+    description: Option<String>,
+    custom_url: Option<String>,
+    country: Option<String>,
+    published_at: Option<String>,
+    #[serde(default)]
+    thumbnails: ChannelThumbnailsReturn,
+}
+
+#[derive(Default, Deserialize)]
+struct ChannelThumbnailsReturn {
+    default: Option<ChannelThumbnailReturn>,
+    medium: Option<ChannelThumbnailReturn>,
+    high: Option<ChannelThumbnailReturn>,
+}
+
+#[derive(Deserialize)]
+struct ChannelThumbnailReturn {
+    url: String,
+}
+
+// Counts are returned as strings by the API
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChannelStatisticsReturn {
+    view_count: Option<String>,
+    subscriber_count: Option<String>,
+    #[serde(default)]
+    hidden_subscriber_count: bool,
+    video_count: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ChannelBrandingReturn {
+    channel: Option<ChannelBrandingChannelReturn>,
+    image: Option<ChannelBrandingImageReturn>,
+}
+
+#[derive(Deserialize)]
+struct ChannelBrandingChannelReturn {
+    keywords: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChannelBrandingImageReturn {
+    banner_external_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChannelTopicDetailsReturn {
+    #[serde(default)]
+    topic_categories: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChannelStatusReturn {
+    made_for_kids: Option<bool>,
+}
+
+impl ChannelDetailsItemReturn {
+    fn to_channel_details(&self) -> ChannelDetails {
+        let thumbnails = &self.snippet.thumbnails;
+        let statistics = self.statistics.as_ref();
+        let branding = self.branding_settings.as_ref();
+        let hidden_subscriber_count = statistics
+            .map(|statistics| statistics.hidden_subscriber_count)
+            .unwrap_or_default();
+        let count = |value: Option<&String>| value.and_then(|value| value.parse().ok());
+
+        ChannelDetails {
+            description: self.snippet.description.clone(),
+            custom_url: self.snippet.custom_url.clone(),
+            country: self.snippet.country.clone(),
+            published_at: self.snippet.published_at.clone(),
+            avatar_url: thumbnails
+                .high
+                .as_ref()
+                .or(thumbnails.medium.as_ref())
+                .or(thumbnails.default.as_ref())
+                .map(|thumbnail| thumbnail.url.clone()),
+            banner_url: branding
+                .and_then(|branding| branding.image.as_ref())
+                .and_then(|image| image.banner_external_url.clone()),
+            keywords: branding
+                .and_then(|branding| branding.channel.as_ref())
+                .and_then(|channel| channel.keywords.as_deref())
+                .map(split_keywords)
+                .unwrap_or_default(),
+            subscriber_count: if hidden_subscriber_count {
+                None
+            } else {
+                count(statistics.and_then(|statistics| statistics.subscriber_count.as_ref()))
+            },
+            hidden_subscriber_count,
+            video_count: count(statistics.and_then(|statistics| statistics.video_count.as_ref())),
+            view_count: count(statistics.and_then(|statistics| statistics.view_count.as_ref())),
+            topic_categories: self
+                .topic_details
+                .as_ref()
+                .map(|topic_details| topic_details.topic_categories.clone())
+                .unwrap_or_default(),
+            made_for_kids: self.status.as_ref().and_then(|status| status.made_for_kids),
+        }
+    }
+}
+
 pub async fn retrieve_channel_id<T: HttpClientTrait>(
     handle: &str,
     client: &Arc<T>,
@@ -37,8 +206,8 @@ pub async fn retrieve_channel_id<T: HttpClientTrait>(
     )
     .map_err(|e| RequestError::Other(e.to_string()))?;
 
-    let response = client.get(url.as_str()).await.map_err(RequestError::Http)?;
-    let channel_data: ChannelReturn = process_response(response).await?;
+    let response = client.get(url.as_str()).await?;
+    let channel_data: ChannelReturn = process_response(response)?;
 
     match find_channel_by_handle(&channel_data.items, handle) {
         Ok(channel_snippet) => Ok((
@@ -56,9 +225,136 @@ fn find_channel_by_handle(
     let target_handle = format!("@{}", target_handle);
     match channels
         .iter()
-        .find(|&channel| channel.snippet.channel_handle == target_handle)
-    {
+        // Handles are case insensitive
+        .find(|&channel| {
+            channel
+                .snippet
+                .channel_handle
+                .eq_ignore_ascii_case(&target_handle)
+        }) {
         Some(channel) => Ok(channel.snippet.clone()),
         None => Err(RequestError::NotFound),
     }
 }
+
+/// Returns the id of the channel with a legacy username, https://www.youtube.com/user/{username}
+pub async fn retrieve_channel_id_by_username<T: HttpClientTrait>(
+    username: &str,
+    client: &Arc<T>,
+) -> Result<String, RequestError> {
+    let url = Url::parse_with_params(
+        "https://yt.lemnoslife.com/noKey/channels",
+        &[("part", "id"), ("forUsername", username)],
+    )
+    .map_err(|e| RequestError::Other(e.to_string()))?;
+
+    let response = client.get(url.as_str()).await?;
+    let channel_data: ChannelIdReturn = process_response(response)?;
+
+    channel_data
+        .items
+        .first()
+        .map(|item| item.id.clone())
+        .ok_or(RequestError::NotFound)
+}
+
+/// Returns the handle, if the channel has a valid one, and the title of a channel
+pub async fn retrieve_channel_by_id<T: HttpClientTrait>(
+    channel_id: &str,
+    client: &Arc<T>,
+) -> Result<(Option<Handle>, String), RequestError> {
+    let url = Url::parse_with_params(
+        "https://yt.lemnoslife.com/noKey/channels",
+        &[("part", "snippet"), ("id", channel_id)],
+    )
+    .map_err(|e| RequestError::Other(e.to_string()))?;
+
+    let response = client.get(url.as_str()).await?;
+    let channel_data: ChannelByIdReturn = process_response(response)?;
+    let snippet = &channel_data
+        .items
+        .first()
+        .ok_or(RequestError::NotFound)?
+        .snippet;
+
+    Ok((snippet.handle(), snippet.title.clone()))
+}
+
+type ChannelsByIds = (
+    Vec<(ChannelId, Option<Handle>, String)>,
+    Vec<SkippedItem<ChannelId>>,
+);
+
+/// Returns the id, handle and title of the channels matching up to 50 ids, along with the
+/// items that could not be decoded, ids that do not exist are left out
+pub async fn retrieve_channels_by_ids<T: HttpClientTrait>(
+    channel_ids: &[&str],
+    client: &Arc<T>,
+) -> Result<ChannelsByIds, RequestError> {
+    let url = Url::parse_with_params(
+        "https://yt.lemnoslife.com/noKey/channels",
+        &[
+            ("part", "snippet"),
+            ("id", channel_ids.join(",").as_str()),
+            ("maxResults", channel_ids.len().to_string().as_str()),
+        ],
+    )
+    .map_err(|e| RequestError::Other(e.to_string()))?;
+
+    let response = client.get(url.as_str()).await?;
+    let (items, skipped) =
+        process_batch_response::<ChannelByIdItemReturn, _>(response, ChannelId::new_unchecked)?;
+
+    Ok((
+        items
+            .into_iter()
+            .map(|(id, item)| (id, item.snippet.handle(), item.snippet.title))
+            .collect(),
+        skipped,
+    ))
+}
+
+pub async fn retrieve_channel_details<T: HttpClientTrait>(
+    channel_id: &str,
+    options: &RequestOptions,
+    client: &Arc<T>,
+) -> Result<ChannelDetails, RequestError> {
+    let mut params = options.query(DEFAULT_CHANNEL_PARTS);
+    params.push(("id", channel_id.to_owned()));
+    let url = Url::parse_with_params("https://yt.lemnoslife.com/noKey/channels", &params)
+        .map_err(|e| RequestError::Other(e.to_string()))?;
+
+    let response = client.get(url.as_str()).await?;
+    let channel_data: ChannelDetailsReturn = process_response(response)?;
+
+    channel_data
+        .items
+        .first()
+        .map(ChannelDetailsItemReturn::to_channel_details)
+        .ok_or(RequestError::NotFound)
+}
+
+/// Splits the keywords of a channel, they are separated by spaces and the ones
+/// containing spaces are wrapped in double quotes
+fn split_keywords(keywords: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for character in keywords.chars() {
+        match character {
+            '"' => quoted = !quoted,
+            ' ' if !quoted => {
+                if !current.is_empty() {
+                    result.push(std::mem::take(&mut current));
+                }
+            }
+            _ => current.push(character),
+        }
+    }
+    if !current.is_empty() {
+        result.push(current);
+    }
+
+    result
+}
@@ -0,0 +1,215 @@
+use super::utils::{decode_items, process_response, RequestError};
+use crate::utils::http_client::HttpClientTrait;
+use crate::youtube::comment::{
+    Comment, CommentReplyOptions, CommentThread, CommentThreadOptions, TextFormat,
+};
+use crate::youtube::id::{ChannelId, VideoId};
+use crate::youtube::page::Page;
+use serde::Deserialize;
+use std::sync::Arc;
+use url::Url;
+
+// Items are kept as raw json and decoded one by one, so a single invalid comment does not
+// fail the whole page
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CommentListReturn {
+    items: Vec<serde_json::Value>,
+    next_page_token: Option<String>,
+    page_info: Option<PageInfoReturn>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageInfoReturn {
+    total_results: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct CommentThreadReturn {
+    snippet: CommentThreadSnippetReturn,
+    replies: Option<CommentRepliesReturn>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CommentThreadSnippetReturn {
+    channel_id: Option<String>,
+    video_id: Option<String>,
+    top_level_comment: CommentReturn,
+    #[serde(default)]
+    can_reply: bool,
+    #[serde(default)]
+    total_reply_count: u64,
+}
+
+#[derive(Deserialize)]
+struct CommentRepliesReturn {
+    #[serde(default)]
+    comments: Vec<CommentReturn>,
+}
+
+#[derive(Deserialize)]
+struct CommentReturn {
+    id: String,
+    snippet: CommentSnippetReturn,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CommentSnippetReturn {
+    video_id: Option<String>,
+    #[serde(default)]
+    author_display_name: String,
+    author_profile_image_url: Option<String>,
+    author_channel_id: Option<AuthorChannelIdReturn>,
+    #[serde(default)]
+    text_display: String,
+    text_original: Option<String>,
+    #[serde(default)]
+    like_count: u64,
+    #[serde(default)]
+    published_at: String,
+    #[serde(default)]
+    updated_at: String,
+    parent_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AuthorChannelIdReturn {
+    value: String,
+}
+
+impl CommentReturn {
+    fn to_comment(&self) -> Comment {
+        let snippet = &self.snippet;
+
+        Comment {
+            id: self.id.clone(),
+            video_id: snippet.video_id.clone().map(VideoId::new_unchecked),
+            author_name: snippet.author_display_name.clone(),
+            author_channel_id: snippet
+                .author_channel_id
+                .as_ref()
+                .map(|channel| ChannelId::new_unchecked(channel.value.clone())),
+            author_profile_image_url: snippet.author_profile_image_url.clone(),
+            text: snippet.text_display.clone(),
+            // Only returned to the author, as an empty string to everyone else
+            text_original: snippet
+                .text_original
+                .clone()
+                .filter(|text| !text.is_empty()),
+            like_count: snippet.like_count,
+            published_at: snippet.published_at.clone(),
+            updated_at: snippet.updated_at.clone(),
+            reply_count: 0,
+            parent_id: snippet.parent_id.clone(),
+        }
+    }
+}
+
+impl CommentThreadReturn {
+    fn to_comment_thread(&self, id: String, text_format: TextFormat) -> CommentThread {
+        let snippet = &self.snippet;
+        let video_id = snippet.video_id.clone().map(VideoId::new_unchecked);
+
+        CommentThread {
+            id,
+            video_id: video_id.clone(),
+            channel_id: snippet.channel_id.clone().map(ChannelId::new_unchecked),
+            comment: Comment {
+                reply_count: snippet.total_reply_count,
+                video_id: video_id.clone(),
+                ..snippet.top_level_comment.to_comment()
+            },
+            replies: self
+                .replies
+                .iter()
+                .flat_map(|replies| &replies.comments)
+                .map(|reply| Comment {
+                    video_id: video_id.clone(),
+                    ..reply.to_comment()
+                })
+                .collect(),
+            can_reply: snippet.can_reply,
+            text_format,
+        }
+    }
+}
+
+/// Returns a page of the comment threads matching a filter, e.g. `("videoId", video_id)`
+pub async fn retrieve_comment_threads<T: HttpClientTrait>(
+    filter: (&str, &str),
+    options: &CommentThreadOptions,
+    client: &Arc<T>,
+) -> Result<Page<CommentThread>, RequestError> {
+    let mut params = vec![
+        ("part", "snippet,replies".to_owned()),
+        (filter.0, filter.1.to_owned()),
+        ("order", options.order.as_str().to_owned()),
+        ("textFormat", options.text_format.as_str().to_owned()),
+    ];
+    if let Some(search_terms) = &options.search_terms {
+        params.push(("searchTerms", search_terms.clone()));
+    }
+    // The field is public, so the range of `with_max_results` is enforced again here
+    if let Some(max_results) = options.max_results {
+        params.push(("maxResults", max_results.clamp(1, 100).to_string()));
+    }
+    if let Some(page_token) = &options.page_token {
+        params.push(("pageToken", page_token.clone()));
+    }
+    let url = Url::parse_with_params("https://yt.lemnoslife.com/noKey/commentThreads", &params)
+        .map_err(|e| RequestError::Other(e.to_string()))?;
+
+    let response = client.get(url.as_str()).await?;
+    let thread_data: CommentListReturn = process_response(response)?;
+    let (threads, skipped) = decode_items(thread_data.items, |id| id);
+
+    Ok(Page {
+        items: threads
+            .into_iter()
+            .map(|(id, thread): (String, CommentThreadReturn)| {
+                thread.to_comment_thread(id, options.text_format)
+            })
+            .collect(),
+        next_page_token: thread_data.next_page_token,
+        total_results: thread_data.page_info.and_then(|info| info.total_results),
+        skipped,
+    })
+}
+
+/// Returns a page of the replies to a top level comment
+pub async fn retrieve_comment_replies<T: HttpClientTrait>(
+    parent_id: &str,
+    options: &CommentReplyOptions,
+    client: &Arc<T>,
+) -> Result<Page<Comment>, RequestError> {
+    let mut params = vec![
+        ("part", "snippet".to_owned()),
+        ("parentId", parent_id.to_owned()),
+        ("textFormat", options.text_format.as_str().to_owned()),
+    ];
+    if let Some(max_results) = options.max_results {
+        params.push(("maxResults", max_results.clamp(1, 100).to_string()));
+    }
+    if let Some(page_token) = &options.page_token {
+        params.push(("pageToken", page_token.clone()));
+    }
+    let url = Url::parse_with_params("https://yt.lemnoslife.com/noKey/comments", &params)
+        .map_err(|e| RequestError::Other(e.to_string()))?;
+
+    let response = client.get(url.as_str()).await?;
+    let comment_data: CommentListReturn = process_response(response)?;
+    let (comments, skipped) = decode_items(comment_data.items, |id| id);
+
+    Ok(Page {
+        items: comments
+            .iter()
+            .map(|(_, comment): &(String, CommentReturn)| comment.to_comment())
+            .collect(),
+        next_page_token: comment_data.next_page_token,
+        total_results: comment_data.page_info.and_then(|info| info.total_results),
+        skipped,
+    })
+}
@@ -1,4 +1,5 @@
 pub mod channel;
+pub mod comment;
 pub mod playlist;
 mod utils;
 pub mod video;
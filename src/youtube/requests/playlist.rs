@@ -1,5 +1,7 @@
-use super::utils::{process_response, RequestError};
+use super::utils::{process_batch_response, process_response, RequestError};
 use crate::utils::http_client::HttpClientTrait;
+use crate::youtube::batch::SkippedItem;
+use crate::youtube::id::{ChannelId, PlaylistId};
 use serde::Deserialize;
 use std::sync::Arc;
 use url::Url;
@@ -28,6 +30,17 @@ struct RelatedPlaylistsReturn {
     uploads: String,
 }
 
+#[derive(Deserialize)]
+struct PlaylistsItemReturn {
+    snippet: PlaylistsSnippetReturn,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PlaylistsSnippetReturn {
+    channel_id: String,
+}
+
 pub async fn retrieve_main_playlist_id<T: HttpClientTrait>(
     channel_id: &str,
     client: &Arc<T>,
@@ -38,17 +51,41 @@ pub async fn retrieve_main_playlist_id<T: HttpClientTrait>(
     )
     .map_err(|e| RequestError::Other(e.to_string()))?;
 
-    let response = client.get(url.as_str()).await.map_err(RequestError::Http)?;
-    let playlist_data: PlaylistReturn = process_response(response).await?;
+    let response = client.get(url.as_str()).await?;
+    let playlist_data: PlaylistReturn = process_response(response)?;
 
-    let playlist_id = playlist_data
+    playlist_data
         .items
         .first()
         .map(|item| item.content_details.related_playlists.uploads.clone())
-        .ok_or(RequestError::NotFound);
+        .ok_or(RequestError::NotFound)
+}
+
+/// Returns the id and channel id of the playlists matching up to 50 ids, along with the
+/// items that could not be decoded, ids that do not exist are left out
+pub async fn retrieve_playlists_by_ids<T: HttpClientTrait>(
+    playlist_ids: &[&str],
+    client: &Arc<T>,
+) -> Result<(Vec<(PlaylistId, ChannelId)>, Vec<SkippedItem<PlaylistId>>), RequestError> {
+    let url = Url::parse_with_params(
+        "https://yt.lemnoslife.com/noKey/playlists",
+        &[
+            ("part", "snippet"),
+            ("id", playlist_ids.join(",").as_str()),
+            ("maxResults", playlist_ids.len().to_string().as_str()),
+        ],
+    )
+    .map_err(|e| RequestError::Other(e.to_string()))?;
+
+    let response = client.get(url.as_str()).await?;
+    let (items, skipped) =
+        process_batch_response::<PlaylistsItemReturn, _>(response, PlaylistId::new_unchecked)?;
 
-    match playlist_id {
-        Ok(playlist_id) => Ok(playlist_id),
-        Err(e) => Err(RequestError::ResponseNotParsed(e.into())),
-    }
+    Ok((
+        items
+            .into_iter()
+            .map(|(id, item)| (id, ChannelId::new_unchecked(item.snippet.channel_id)))
+            .collect(),
+        skipped,
+    ))
 }
@@ -1,6 +1,16 @@
-use reqwest::Response;
+use crate::error::{ApiError, DecodeError, Error};
+use crate::utils::cache::without_api_key;
+use crate::utils::http_client::HttpResponse;
+use crate::youtube::batch::SkippedItem;
+use reqwest::StatusCode;
 use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::time::Duration;
 use thiserror::Error;
+use url::Url;
+
+/// Maximum number of characters of the body kept in a decode error
+const BODY_EXCERPT_LENGTH: usize = 500;
 
 #[derive(Error, Debug)]
 pub enum RequestError {
@@ -9,19 +19,236 @@ pub enum RequestError {
     #[error("{0}")]
     Other(String),
     #[error(transparent)]
-    ResponseNotParsed(#[from] anyhow::Error),
+    ResponseNotParsed(#[from] DecodeError),
     #[error(transparent)]
-    Http(#[from] reqwest::Error),
+    Client(#[from] Error),
+    #[error("Request failed: {error}")]
+    Status {
+        retry_after: Option<Duration>,
+        error: ApiError,
+    },
+}
+
+#[derive(Deserialize)]
+struct ErrorEnvelopeReturn {
+    error: ErrorReturn,
+}
+
+#[derive(Deserialize)]
+struct ErrorReturn {
+    code: Option<u16>,
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    errors: Vec<ErrorDetailReturn>,
+}
+
+#[derive(Deserialize)]
+struct ErrorDetailReturn {
+    reason: Option<String>,
+    domain: Option<String>,
+}
+
+impl ErrorReturn {
+    fn to_api_error(&self, status: StatusCode) -> ApiError {
+        let detail = self.errors.first();
+        ApiError {
+            code: self.code.unwrap_or(status.as_u16()),
+            message: self.message.clone(),
+            reason: detail.and_then(|detail| detail.reason.clone()),
+            domain: detail.and_then(|detail| detail.domain.clone()),
+        }
+    }
+}
+
+/// A successful response whose body has not been decoded yet
+pub struct RawResponse {
+    url: String,
+    status: StatusCode,
+    retry_after: Option<Duration>,
+    body: String,
+}
+
+impl RawResponse {
+    pub fn decode<T: DeserializeOwned>(&self) -> Result<T, RequestError> {
+        let deserializer = &mut serde_json::Deserializer::from_str(&self.body);
+        serde_path_to_error::deserialize(deserializer).map_err(|e| match self.envelope() {
+            Some(envelope) => RequestError::Status {
+                retry_after: self.retry_after,
+                error: envelope.error.to_api_error(self.status),
+            },
+            None => self.decode_error(e.path().to_string(), e.into_inner().to_string()),
+        })
+    }
+
+    /// Builds a decode error for a field of this response
+    pub fn decode_error(&self, path: String, message: String) -> RequestError {
+        RequestError::ResponseNotParsed(DecodeError {
+            url: self.url.clone(),
+            status: self.status.as_u16(),
+            path,
+            message,
+            body_excerpt: excerpt(&self.body),
+        })
+    }
+
+    // The API may answer with its error envelope even when the status is successful
+    fn envelope(&self) -> Option<ErrorEnvelopeReturn> {
+        serde_json::from_str(&self.body).ok()
+    }
+}
+
+pub fn read_response(response: HttpResponse) -> Result<RawResponse, RequestError> {
+    let raw = RawResponse {
+        url: Url::parse(&response.url)
+            .map(|url| redact_url(&url))
+            .unwrap_or_else(|_| response.url.clone()),
+        status: StatusCode::from_u16(response.status)
+            .map_err(|e| RequestError::Other(e.to_string()))?,
+        retry_after: response
+            .header("retry-after")
+            .and_then(|value| value.trim().parse().ok())
+            .map(Duration::from_secs),
+        body: response.body,
+    };
+
+    if !raw.status.is_success() {
+        let error = match raw.envelope() {
+            Some(envelope) => envelope.error.to_api_error(raw.status),
+            None => ApiError {
+                code: raw.status.as_u16(),
+                message: raw.status.canonical_reason().unwrap_or_default().to_owned(),
+                reason: None,
+                domain: None,
+            },
+        };
+        return Err(RequestError::Status {
+            retry_after: raw.retry_after,
+            error,
+        });
+    }
+
+    Ok(raw)
 }
 
-pub async fn process_response<T: DeserializeOwned>(response: Response) -> Result<T, RequestError> {
-    if response.status().is_success() {
-        let parsed_data: T = response
-            .json()
-            .await
-            .map_err(|e| RequestError::ResponseNotParsed(e.into()))?;
-        return Ok(parsed_data);
+pub fn process_response<T: DeserializeOwned>(response: HttpResponse) -> Result<T, RequestError> {
+    read_response(response)?.decode()
+}
+
+// Items are kept as raw json so a single invalid item does not fail the whole batch
+#[derive(Deserialize)]
+struct BatchReturn {
+    items: Vec<serde_json::Value>,
+}
+
+/// Decoded items of a batch response with their id, and the skipped ones
+pub type BatchItems<I, T> = (Vec<(I, T)>, Vec<SkippedItem<I>>);
+
+/// Decodes the items of a batch response one by one along with their id, the items that
+/// have no id or cannot be decoded are skipped
+pub fn process_batch_response<T: DeserializeOwned, I>(
+    response: HttpResponse,
+    new_id: impl Fn(String) -> I,
+) -> Result<BatchItems<I, T>, RequestError> {
+    let batch: BatchReturn = process_response(response)?;
+
+    Ok(decode_items(batch.items, new_id))
+}
+
+/// Decodes the raw items of a response one by one along with their id, the items that have
+/// no id or cannot be decoded are skipped
+pub fn decode_items<T: DeserializeOwned, I>(
+    raw_items: Vec<serde_json::Value>,
+    new_id: impl Fn(String) -> I,
+) -> BatchItems<I, T> {
+    let mut items = Vec::new();
+    let mut skipped = Vec::new();
+    for (index, item) in raw_items.into_iter().enumerate() {
+        let Some(id) = item.get("id").and_then(|id| id.as_str()).map(str::to_owned) else {
+            skipped.push(SkippedItem {
+                id: None,
+                path: format!("items[{}].id", index),
+                message: "missing id".to_owned(),
+            });
+            continue;
+        };
+
+        match serde_path_to_error::deserialize(item) {
+            Ok(decoded) => items.push((new_id(id), decoded)),
+            Err(e) => skipped.push(SkippedItem {
+                id: Some(new_id(id)),
+                path: match e.path().to_string().as_str() {
+                    "." => format!("items[{}]", index),
+                    path => format!("items[{}].{}", index, path),
+                },
+                message: e.into_inner().to_string(),
+            }),
+        }
+    }
+    (items, skipped)
+}
+
+/// Removes the API key from an url so it can be shown in errors
+fn redact_url(url: &Url) -> String {
+    without_api_key(url.clone()).to_string()
+}
+
+fn excerpt(body: &str) -> String {
+    match body.char_indices().nth(BODY_EXCERPT_LENGTH) {
+        Some((index, _)) => format!("{}...", &body[..index]),
+        None => body.to_owned(),
+    }
+}
+
+impl From<RequestError> for Error {
+    fn from(error: RequestError) -> Self {
+        match error {
+            RequestError::NotFound => Error::NotFound,
+            RequestError::Other(msg) => Error::InvalidInput(msg),
+            RequestError::ResponseNotParsed(e) => Error::Decode(Box::new(e)),
+            RequestError::Client(e) => e,
+            RequestError::Status { retry_after, error } => {
+                match (
+                    StatusCode::from_u16(error.code).ok(),
+                    error.reason.as_deref(),
+                ) {
+                    (_, Some("quotaExceeded" | "dailyLimitExceeded")) => Error::QuotaExceeded,
+                    (_, Some("rateLimitExceeded" | "userRateLimitExceeded")) => {
+                        Error::RateLimited { retry_after }
+                    }
+                    (Some(StatusCode::TOO_MANY_REQUESTS), _) => Error::RateLimited { retry_after },
+                    (Some(StatusCode::NOT_FOUND), _) => Error::NotFound,
+                    (Some(StatusCode::BAD_REQUEST), _) => Error::InvalidInput(error.message),
+                    (Some(status), _) if status.is_server_error() => Error::Unavailable,
+                    _ => Error::Api(error),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_key_is_removed_from_urls() {
+        let url = Url::parse(
+            "https://www.googleapis.com/youtube/v3/videos?part=snippet&key=secret&id=dQw4w9WgXcQ",
+        )
+        .unwrap();
+
+        assert_eq!(
+            redact_url(&url),
+            "https://www.googleapis.com/youtube/v3/videos?part=snippet&id=dQw4w9WgXcQ"
+        );
     }
 
-    Err(RequestError::Http(response.error_for_status().unwrap_err()))
+    #[test]
+    fn long_bodies_are_truncated() {
+        let body = "a".repeat(BODY_EXCERPT_LENGTH + 10);
+
+        assert_eq!(excerpt(&body).len(), BODY_EXCERPT_LENGTH + 3);
+        assert_eq!(excerpt("{}"), "{}");
+    }
 }
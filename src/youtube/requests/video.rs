@@ -1,30 +1,279 @@
+use crate::youtube::batch::SkippedItem;
+use crate::youtube::id::{ChannelId, VideoId};
+use crate::youtube::listing::{
+    ItemError, ItemErrorKind, ListingOptions, ParseMode, UnavailablePolicy, VideoListing,
+};
+use crate::youtube::parts::{RequestOptions, DEFAULT_VIDEO_PARTS};
 use crate::youtube::requests::utils::RequestError;
+use crate::youtube::thumbnail::{Thumbnail, ThumbnailSize, Thumbnails};
+use crate::youtube::video::{
+    Availability, License, LiveBroadcastContent, LiveStreamingDetails, PrivacyStatus,
+    VideoContentDetails, VideoStatistics, VideoStatus,
+};
 use crate::{utils::http_client::HttpClientTrait, youtube::video::Video};
 use serde::Deserialize;
 use std::sync::Arc;
 use url::Url;
 
-use super::utils::process_response;
+use super::utils::{process_batch_response, process_response, read_response};
 
 #[derive(Deserialize)]
 struct VideoReturn {
     items: Vec<VideoItemReturns>,
 }
 
+// Items are kept as raw json so a single invalid item does not fail the whole listing
+#[derive(Deserialize)]
+struct VideoListReturn {
+    items: Vec<serde_json::Value>,
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct VideoItemReturns {
+    #[serde(default)]
     snippet: VideoSnippetReturn,
+    live_streaming_details: Option<LiveStreamingDetailsReturn>,
+    status: Option<VideoStatusReturn>,
+    content_details: Option<VideoContentDetailsReturn>,
+    statistics: Option<VideoStatisticsReturn>,
+}
+
+// The API returns every count as a string
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VideoStatisticsReturn {
+    view_count: Option<String>,
+    like_count: Option<String>,
+    comment_count: Option<String>,
+}
+
+impl VideoStatisticsReturn {
+    fn to_statistics(&self) -> VideoStatistics {
+        let count = |count: &Option<String>| count.as_deref().and_then(|count| count.parse().ok());
+
+        VideoStatistics {
+            view_count: count(&self.view_count),
+            like_count: count(&self.like_count),
+            comment_count: count(&self.comment_count),
+        }
+    }
+}
+
+// Playlist items only return `privacyStatus`, videos return every field
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VideoStatusReturn {
+    privacy_status: Option<String>,
+    embeddable: Option<bool>,
+    made_for_kids: Option<bool>,
+    license: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VideoContentDetailsReturn {
+    region_restriction: Option<RegionRestrictionReturn>,
+    content_rating: Option<ContentRatingReturn>,
+    duration: Option<String>,
+    definition: Option<String>,
+    // The API returns whether captions are available as a string
+    caption: Option<String>,
+    licensed_content: Option<bool>,
+}
+
+impl VideoContentDetailsReturn {
+    fn to_content_details(&self) -> VideoContentDetails {
+        VideoContentDetails {
+            duration: self.duration.clone(),
+            definition: self.definition.clone(),
+            caption: self.caption.as_deref() == Some("true"),
+            licensed_content: self.licensed_content.unwrap_or_default(),
+        }
+    }
 }
 
 #[derive(Deserialize)]
+struct RegionRestrictionReturn {
+    allowed: Option<Vec<String>>,
+    #[serde(default)]
+    blocked: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContentRatingReturn {
+    yt_rating: Option<String>,
+}
+
+impl VideoItemReturns {
+    fn to_video(&self, video_id: &str) -> Video {
+        Video {
+            live_streaming_details: self
+                .live_streaming_details
+                .as_ref()
+                .map(LiveStreamingDetailsReturn::to_live_streaming_details),
+            availability: self.availability(),
+            ..self.snippet.to_video(video_id)
+        }
+    }
+
+    /// Playlists keep placeholders for private and deleted videos, they can be told apart
+    /// by their privacy status. The placeholder title is only trusted when the status does
+    /// not tell, as a public video can be named "Private video"
+    fn availability(&self) -> Availability {
+        let privacy_status = self
+            .status
+            .as_ref()
+            .and_then(|status| status.privacy_status.as_deref());
+        let title = self.snippet.title.as_deref().unwrap_or_default();
+
+        match (privacy_status, title) {
+            (Some("private"), _) => Availability::Private,
+            (Some("privacyStatusUnspecified"), "Deleted video") => Availability::Deleted,
+            (Some("privacyStatusUnspecified"), _) => Availability::Unavailable,
+            (Some(_), _) => Availability::Available,
+            (None, "Deleted video") => Availability::Deleted,
+            (None, "Private video") => Availability::Private,
+            (None, _) => Availability::Available,
+        }
+    }
+
+    /// Video searched by its id, along with the sections only returned by the videos endpoint
+    fn to_detailed_video(&self, video_id: &str) -> Video {
+        Video {
+            status: self.to_status(),
+            statistics: self
+                .statistics
+                .as_ref()
+                .map(VideoStatisticsReturn::to_statistics),
+            content_details: self
+                .content_details
+                .as_ref()
+                .map(VideoContentDetailsReturn::to_content_details),
+            ..self.to_video(video_id)
+        }
+    }
+
+    fn to_status(&self) -> Option<VideoStatus> {
+        let status = self.status.as_ref()?;
+        let content_details = self.content_details.as_ref();
+        let region_restriction =
+            content_details.and_then(|details| details.region_restriction.as_ref());
+
+        Some(VideoStatus {
+            privacy_status: status
+                .privacy_status
+                .as_deref()
+                .map(PrivacyStatus::from)
+                .unwrap_or_default(),
+            embeddable: status.embeddable.unwrap_or(true),
+            made_for_kids: status.made_for_kids.unwrap_or_default(),
+            license: status
+                .license
+                .as_deref()
+                .map(License::from)
+                .unwrap_or_default(),
+            age_restricted: content_details
+                .and_then(|details| details.content_rating.as_ref())
+                .and_then(|rating| rating.yt_rating.as_deref())
+                == Some("ytAgeRestricted"),
+            allowed_regions: region_restriction.and_then(|regions| regions.allowed.clone()),
+            blocked_regions: region_restriction
+                .map(|regions| regions.blocked.clone())
+                .unwrap_or_default(),
+        })
+    }
+}
+
+// Every field is optional so the parts and fields of the options can leave them out
+#[derive(Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct VideoSnippetReturn {
-    title: String,
-    description: String,
+    title: Option<String>,
+    description: Option<String>,
+    #[serde(default)]
     thumbnails: VideoThumbnailReturn,
-    published_at: String,
+    published_at: Option<String>,
     resource_id: Option<VideoResourceIdReturn>,
+    channel_id: Option<String>,
+    #[serde(default)]
+    channel_title: String,
+    video_owner_channel_id: Option<String>,
+    video_owner_channel_title: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    category_id: Option<String>,
+    default_language: Option<String>,
+    default_audio_language: Option<String>,
+    live_broadcast_content: Option<String>,
+}
+
+impl VideoSnippetReturn {
+    fn to_video(&self, video_id: &str) -> Video {
+        // Playlist items report the owner of the playlist as `channelId`, the uploader
+        // of the video is in `videoOwnerChannelId`
+        let channel_id = self
+            .video_owner_channel_id
+            .as_ref()
+            .or(self.channel_id.as_ref());
+        let channel_title = self
+            .video_owner_channel_title
+            .as_ref()
+            .unwrap_or(&self.channel_title);
+
+        Video {
+            title: self.title.clone(),
+            description: self.description.clone(),
+            published_at: self.published_at.clone(),
+            channel_id: channel_id.cloned().map(ChannelId::new_unchecked),
+            channel_title: channel_title.clone(),
+            tags: self.tags.clone(),
+            category_id: self.category_id.clone(),
+            default_language: self.default_language.clone(),
+            default_audio_language: self.default_audio_language.clone(),
+            live_broadcast_content: self
+                .live_broadcast_content
+                .as_deref()
+                .map(LiveBroadcastContent::from)
+                .unwrap_or_default(),
+            ..Video::new(
+                VideoId::new_unchecked(video_id),
+                String::new(),
+                String::new(),
+                String::new(),
+                self.thumbnails.to_thumbnails(video_id),
+            )
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LiveStreamingDetailsReturn {
+    scheduled_start_time: Option<String>,
+    scheduled_end_time: Option<String>,
+    actual_start_time: Option<String>,
+    actual_end_time: Option<String>,
+    // The API returns the number of viewers as a string
+    concurrent_viewers: Option<String>,
+    active_live_chat_id: Option<String>,
+}
+
+impl LiveStreamingDetailsReturn {
+    fn to_live_streaming_details(&self) -> LiveStreamingDetails {
+        LiveStreamingDetails {
+            scheduled_start_time: self.scheduled_start_time.clone(),
+            scheduled_end_time: self.scheduled_end_time.clone(),
+            actual_start_time: self.actual_start_time.clone(),
+            actual_end_time: self.actual_end_time.clone(),
+            concurrent_viewers: self
+                .concurrent_viewers
+                .as_deref()
+                .and_then(|viewers| viewers.parse().ok()),
+            active_live_chat_id: self.active_live_chat_id.clone(),
+        }
+    }
 }
 
 #[derive(Deserialize)]
@@ -33,82 +282,169 @@ struct VideoResourceIdReturn {
     video_id: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Default, Deserialize)]
 struct VideoThumbnailReturn {
-    high: VideoThumbnailItemReturn,
+    default: Option<VideoThumbnailItemReturn>,
+    medium: Option<VideoThumbnailItemReturn>,
+    high: Option<VideoThumbnailItemReturn>,
+    standard: Option<VideoThumbnailItemReturn>,
+    maxres: Option<VideoThumbnailItemReturn>,
 }
 
 #[derive(Deserialize)]
 struct VideoThumbnailItemReturn {
     url: String,
+    width: Option<u32>,
+    height: Option<u32>,
+}
+
+impl VideoThumbnailReturn {
+    fn to_thumbnails(&self, video_id: &str) -> Thumbnails {
+        let thumbnail = |item: &Option<VideoThumbnailItemReturn>, size: ThumbnailSize| {
+            item.as_ref().map(|item| {
+                let (width, height) = size.dimensions();
+                Thumbnail::new(
+                    item.url.clone(),
+                    item.width.unwrap_or(width),
+                    item.height.unwrap_or(height),
+                )
+            })
+        };
+
+        Thumbnails {
+            default: thumbnail(&self.default, ThumbnailSize::Default),
+            medium: thumbnail(&self.medium, ThumbnailSize::Medium),
+            high: thumbnail(&self.high, ThumbnailSize::High),
+            standard: thumbnail(&self.standard, ThumbnailSize::Standard),
+            maxres: thumbnail(&self.maxres, ThumbnailSize::Maxres),
+            ..Thumbnails::for_video(video_id)
+        }
+    }
 }
 
 pub async fn retrieve_latest_videos<T: HttpClientTrait>(
     playlist_id: &str,
     number_of_videos: i32,
+    options: &ListingOptions,
     client: Arc<T>,
-) -> Result<Vec<Video>, RequestError> {
+) -> Result<VideoListing, RequestError> {
     let url = Url::parse_with_params(
         "https://yt.lemnoslife.com/noKey/playlistItems",
         &[
-            ("part", "snippet"),
+            ("part", "snippet,status"),
             ("maxResults", number_of_videos.to_string().as_str()),
             ("playlistId", playlist_id),
         ],
     )
     .map_err(|e| RequestError::Other(e.to_string()))?;
 
-    let response = client.get(url.as_str()).await.map_err(RequestError::Http)?;
-    let video_data: VideoReturn = process_response::<VideoReturn>(response).await?;
+    let response = client.get(url.as_str()).await?;
+    let raw_response = read_response(response)?;
+    let video_data: VideoListReturn = raw_response.decode()?;
 
     if video_data.items.is_empty() {
         return Err(RequestError::NotFound);
     }
 
-    Ok(video_data
-        .items
-        .iter()
-        .map(|item| {
-            let resource = &item.snippet.resource_id;
-            match resource {
-                Some(resource) => Video::new(
-                    resource.video_id.clone(),
-                    item.snippet.title.clone(),
-                    item.snippet.description.clone(),
-                    item.snippet.published_at.clone(),
-                    item.snippet.thumbnails.high.url.clone(),
-                ),
-                None => Video::new(
-                    "".to_owned(),
-                    item.snippet.title.clone(),
-                    item.snippet.description.clone(),
-                    item.snippet.published_at.clone(),
-                    item.snippet.thumbnails.high.url.clone(),
-                ),
-            }
-        })
-        .collect())
+    let mut listing = VideoListing::default();
+    for (index, item) in video_data.items.into_iter().enumerate() {
+        match parse_playlist_item(index, item) {
+            Ok(video) if video.is_available() => listing.videos.push(video),
+            Ok(video) => match options.unavailable {
+                UnavailablePolicy::Include => listing.videos.push(video),
+                UnavailablePolicy::Exclude => {}
+                UnavailablePolicy::Report => listing.skipped.push(ItemError {
+                    index,
+                    kind: ItemErrorKind::Unavailable(video.availability),
+                    video_id: Some(video.id),
+                }),
+            },
+            Err(error) => match options.mode {
+                ParseMode::Lenient => listing.skipped.push(error),
+                ParseMode::Strict => {
+                    let (path, message) = match error.kind {
+                        ItemErrorKind::Malformed { path, message } => (path, message),
+                        _ => (
+                            "snippet.resourceId.videoId".to_owned(),
+                            error.kind.to_string(),
+                        ),
+                    };
+                    return Err(
+                        raw_response.decode_error(format!("items[{}].{}", index, path), message)
+                    );
+                }
+            },
+        }
+    }
+
+    Ok(listing)
+}
+
+fn parse_playlist_item(index: usize, item: serde_json::Value) -> Result<Video, ItemError> {
+    // Read before decoding so malformed items can still be identified
+    let raw_video_id = item
+        .pointer("/snippet/resourceId/videoId")
+        .and_then(|video_id| video_id.as_str())
+        .map(VideoId::new_unchecked);
+
+    let item: VideoItemReturns = serde_path_to_error::deserialize(item).map_err(|e| ItemError {
+        index,
+        video_id: raw_video_id,
+        kind: ItemErrorKind::Malformed {
+            path: e.path().to_string(),
+            message: e.into_inner().to_string(),
+        },
+    })?;
+
+    match &item.snippet.resource_id {
+        Some(resource) if !resource.video_id.is_empty() => Ok(item.to_video(&resource.video_id)),
+        _ => Err(ItemError {
+            index,
+            video_id: None,
+            kind: ItemErrorKind::MissingVideoId,
+        }),
+    }
 }
 
 pub async fn retrieve_video_by_id<T: HttpClientTrait>(
     video_id: &str,
+    options: &RequestOptions,
     client: Arc<T>,
 ) -> Result<Video, RequestError> {
-    let url = Url::parse_with_params(
-        "https://yt.lemnoslife.com/noKey/videos",
-        &[("part", "snippet"), ("id", video_id)],
-    )
-    .map_err(|e| RequestError::Other(e.to_string()))?;
+    let mut params = options.query(DEFAULT_VIDEO_PARTS);
+    params.push(("id", video_id.to_owned()));
+    let url = Url::parse_with_params("https://yt.lemnoslife.com/noKey/videos", &params)
+        .map_err(|e| RequestError::Other(e.to_string()))?;
 
-    let response = client.get(url.as_str()).await.map_err(RequestError::Http)?;
-    let video_return: VideoReturn = process_response::<VideoReturn>(response).await?;
+    let response = client.get(url.as_str()).await?;
+    let video_return: VideoReturn = process_response::<VideoReturn>(response)?;
     let video_data = video_return.items.first().ok_or(RequestError::NotFound)?;
 
-    Ok(Video::new(
-        video_id.to_string(),
-        video_data.snippet.title.clone(),
-        video_data.snippet.description.clone(),
-        video_data.snippet.published_at.clone(),
-        video_data.snippet.thumbnails.high.url.clone(),
+    Ok(video_data.to_detailed_video(video_id))
+}
+
+/// Returns the videos matching up to 50 ids, along with the items that could not be decoded,
+/// ids that do not exist are left out
+pub async fn retrieve_videos_by_ids<T: HttpClientTrait>(
+    video_ids: &[&str],
+    options: &RequestOptions,
+    client: Arc<T>,
+) -> Result<(Vec<Video>, Vec<SkippedItem<VideoId>>), RequestError> {
+    let mut params = options.batch_query(DEFAULT_VIDEO_PARTS);
+    params.push(("id", video_ids.join(",")));
+    params.push(("maxResults", video_ids.len().to_string()));
+    let url = Url::parse_with_params("https://yt.lemnoslife.com/noKey/videos", &params)
+        .map_err(|e| RequestError::Other(e.to_string()))?;
+
+    let response = client.get(url.as_str()).await?;
+    let (items, skipped) =
+        process_batch_response::<VideoItemReturns, _>(response, VideoId::new_unchecked)?;
+
+    Ok((
+        items
+            .iter()
+            .map(|(id, item)| item.to_detailed_video(id.as_str()))
+            .collect(),
+        skipped,
     ))
 }
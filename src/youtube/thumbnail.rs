@@ -0,0 +1,165 @@
+/// Every thumbnail size YouTube generates for a video, from smallest to largest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ThumbnailSize {
+    Default,
+    Medium,
+    High,
+    Standard,
+    Maxres,
+}
+
+impl ThumbnailSize {
+    /// All sizes ordered from the smallest to the largest one
+    pub const ALL: [ThumbnailSize; 5] = [
+        ThumbnailSize::Default,
+        ThumbnailSize::Medium,
+        ThumbnailSize::High,
+        ThumbnailSize::Standard,
+        ThumbnailSize::Maxres,
+    ];
+
+    /// Name of the image file served by i.ytimg.com for this size
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            ThumbnailSize::Default => "default.jpg",
+            ThumbnailSize::Medium => "mqdefault.jpg",
+            ThumbnailSize::High => "hqdefault.jpg",
+            ThumbnailSize::Standard => "sddefault.jpg",
+            ThumbnailSize::Maxres => "maxresdefault.jpg",
+        }
+    }
+
+    /// Width and height YouTube uses for this size
+    pub fn dimensions(&self) -> (u32, u32) {
+        match self {
+            ThumbnailSize::Default => (120, 90),
+            ThumbnailSize::Medium => (320, 180),
+            ThumbnailSize::High => (480, 360),
+            ThumbnailSize::Standard => (640, 480),
+            ThumbnailSize::Maxres => (1280, 720),
+        }
+    }
+}
+
+/// A single thumbnail image
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Thumbnail {
+    pub url: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Thumbnail {
+    pub fn new(url: String, width: u32, height: u32) -> Self {
+        Self { url, width, height }
+    }
+
+    /// Builds the thumbnail url for a video without asking the API,
+    /// e.g. https://i.ytimg.com/vi/{id}/maxresdefault.jpg
+    pub fn for_video(video_id: &str, size: ThumbnailSize) -> Self {
+        let (width, height) = size.dimensions();
+        Self::new(
+            format!("https://i.ytimg.com/vi/{}/{}", video_id, size.file_name()),
+            width,
+            height,
+        )
+    }
+}
+
+/// Thumbnails returned by the API for a video, sizes the API omitted are `None`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Thumbnails {
+    pub(crate) video_id: String,
+    pub default: Option<Thumbnail>,
+    pub medium: Option<Thumbnail>,
+    pub high: Option<Thumbnail>,
+    pub standard: Option<Thumbnail>,
+    pub maxres: Option<Thumbnail>,
+}
+
+impl Thumbnails {
+    /// Creates an empty set of thumbnails for a video, every size will be built from its id
+    pub fn for_video(video_id: &str) -> Self {
+        Self {
+            video_id: video_id.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the thumbnail of the given size as returned by the API, if any
+    pub fn size(&self, size: ThumbnailSize) -> Option<&Thumbnail> {
+        match size {
+            ThumbnailSize::Default => self.default.as_ref(),
+            ThumbnailSize::Medium => self.medium.as_ref(),
+            ThumbnailSize::High => self.high.as_ref(),
+            ThumbnailSize::Standard => self.standard.as_ref(),
+            ThumbnailSize::Maxres => self.maxres.as_ref(),
+        }
+    }
+
+    /// Returns the thumbnail of the given size, building its url from the video id
+    /// when the API did not return it
+    pub fn get(&self, size: ThumbnailSize) -> Thumbnail {
+        self.size(size)
+            .cloned()
+            .unwrap_or_else(|| Thumbnail::for_video(&self.video_id, size))
+    }
+
+    /// Returns the largest thumbnail returned by the API. If the API did not return any,
+    /// the `high` one is built from the video id, as it exists for every video
+    pub fn best(&self) -> Thumbnail {
+        ThumbnailSize::ALL
+            .iter()
+            .rev()
+            .find_map(|size| self.size(*size).cloned())
+            .unwrap_or_else(|| Thumbnail::for_video(&self.video_id, ThumbnailSize::High))
+    }
+
+    /// Iterates over the thumbnails returned by the API, from the smallest to the largest
+    pub fn iter(&self) -> impl Iterator<Item = &Thumbnail> {
+        ThumbnailSize::ALL
+            .iter()
+            .filter_map(move |size| self.size(*size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_sizes_are_built_from_the_video_id() {
+        let thumbnails = Thumbnails::for_video("dQw4w9WgXcQ");
+
+        assert_eq!(
+            thumbnails.get(ThumbnailSize::Maxres),
+            Thumbnail::new(
+                "https://i.ytimg.com/vi/dQw4w9WgXcQ/maxresdefault.jpg".to_owned(),
+                1280,
+                720
+            )
+        );
+        assert_eq!(
+            thumbnails.best().url,
+            "https://i.ytimg.com/vi/dQw4w9WgXcQ/hqdefault.jpg"
+        );
+        assert_eq!(thumbnails.iter().count(), 0);
+    }
+
+    #[test]
+    fn best_returns_the_largest_available_size() {
+        let thumbnails = Thumbnails {
+            medium: Some(Thumbnail::new("medium".to_owned(), 320, 180)),
+            standard: Some(Thumbnail::new("standard".to_owned(), 640, 480)),
+            ..Thumbnails::for_video("dQw4w9WgXcQ")
+        };
+
+        assert_eq!(thumbnails.best().url, "standard");
+        assert_eq!(thumbnails.get(ThumbnailSize::Medium).url, "medium");
+        assert_eq!(thumbnails.iter().count(), 2);
+    }
+}
@@ -1,66 +1,366 @@
-use thiserror::Error;
+use crate::error::Error;
 
 use crate::utils::http_client::HttpClientTrait;
+use std::fmt;
 use std::sync::Arc;
 
-use super::requests::video::retrieve_video_by_id;
+use super::batch::{self, BatchLookup};
+use super::channel::Channel;
+use super::comment::{CommentThread, CommentThreadOptions};
+use super::id::{ChannelId, VideoId};
+use super::page::Page;
+use super::parts::RequestOptions;
+use super::requests::comment::retrieve_comment_threads;
+use super::requests::video::{retrieve_video_by_id, retrieve_videos_by_ids};
+use super::thumbnail::{ThumbnailSize, Thumbnails};
 
 /// A Video structure, it will contain all data regarding a video
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Video {
-    pub id: String,
-    pub title: String,
-    pub description: String,
-    pub published_at: String,
+    pub id: VideoId,
+    /// `None` when left out by the parts or fields of `RequestOptions`, as are `description`
+    /// and `published_at`
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub published_at: Option<String>,
+    /// Url of the `high` thumbnail, see `thumbnails` for every other size
     pub thumbnail: String,
+    pub thumbnails: Thumbnails,
+    /// Id of the channel that uploaded the video
+    pub channel_id: Option<ChannelId>,
+    pub channel_title: String,
+    /// Tags set by the uploader, only returned when searching a video by its id
+    pub tags: Vec<String>,
+    /// Category of the video, only returned when searching a video by its id
+    pub category_id: Option<String>,
+    pub default_language: Option<String>,
+    pub default_audio_language: Option<String>,
+    pub live_broadcast_content: LiveBroadcastContent,
+    /// Only present for streams and premieres, and only when searching a video by its id
+    pub live_streaming_details: Option<LiveStreamingDetails>,
+    /// Whether the video can be watched, listings include placeholders for private and
+    /// deleted videos
+    pub availability: Availability,
+    /// Privacy, embedding and region restrictions, only returned when searching a video by its id
+    pub status: Option<VideoStatus>,
+    /// Only returned when searching a video by its id with the `statistics` part
+    pub statistics: Option<VideoStatistics>,
+    /// Only returned when searching a video by its id with the `contentDetails` part
+    pub content_details: Option<VideoContentDetails>,
 }
 
-#[derive(Debug, Error)]
-#[error("{msg}")]
-pub struct VideoError {
-    pub source: Option<anyhow::Error>,
-    pub msg: String,
+/// Audience of a video, counts hidden by the uploader are `None`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VideoStatistics {
+    pub view_count: Option<u64>,
+    pub like_count: Option<u64>,
+    pub comment_count: Option<u64>,
+}
+
+/// Length and format of a video
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VideoContentDetails {
+    /// ISO 8601 duration, e.g. `PT3M33S`
+    pub duration: Option<String>,
+    /// `hd` or `sd`
+    pub definition: Option<String>,
+    /// Whether captions are available
+    pub caption: bool,
+    pub licensed_content: bool,
+}
+
+/// Who can watch a video, and where
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VideoStatus {
+    pub privacy_status: PrivacyStatus,
+    /// Whether the video can be played in an embedded player
+    pub embeddable: bool,
+    pub made_for_kids: bool,
+    pub license: License,
+    /// Whether viewers have to be signed in and of age to watch the video
+    pub age_restricted: bool,
+    /// ISO 3166-1 alpha-2 codes of the only regions where the video can be watched,
+    /// `None` when it is not limited to some regions
+    pub allowed_regions: Option<Vec<String>>,
+    /// ISO 3166-1 alpha-2 codes of the regions where the video is blocked
+    pub blocked_regions: Vec<String>,
+}
+
+// Videos can be embedded unless the API says otherwise, as when parsing
+impl Default for VideoStatus {
+    fn default() -> Self {
+        VideoStatus {
+            privacy_status: PrivacyStatus::default(),
+            embeddable: true,
+            made_for_kids: false,
+            license: License::default(),
+            age_restricted: false,
+            allowed_regions: None,
+            blocked_regions: Vec::new(),
+        }
+    }
+}
+
+impl VideoStatus {
+    /// Whether the video can be watched from a region, given as an ISO 3166-1 alpha-2 code
+    pub fn is_playable_in(&self, region: &str) -> bool {
+        let region = region.trim();
+        let listed = |regions: &[String]| {
+            regions
+                .iter()
+                .any(|listed| listed.eq_ignore_ascii_case(region))
+        };
+
+        if self.privacy_status == PrivacyStatus::Private || listed(&self.blocked_regions) {
+            return false;
+        }
+
+        self.allowed_regions
+            .as_ref()
+            .map_or(true, |allowed| listed(allowed))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum PrivacyStatus {
+    #[default]
+    Public,
+    Unlisted,
+    Private,
+}
+
+impl From<&str> for PrivacyStatus {
+    fn from(value: &str) -> Self {
+        match value {
+            "unlisted" => PrivacyStatus::Unlisted,
+            "private" => PrivacyStatus::Private,
+            _ => PrivacyStatus::Public,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum License {
+    /// Standard YouTube license
+    #[default]
+    Youtube,
+    CreativeCommon,
+}
+
+impl From<&str> for License {
+    fn from(value: &str) -> Self {
+        match value {
+            "creativeCommon" => License::CreativeCommon,
+            _ => License::Youtube,
+        }
+    }
+}
+
+/// Whether a video of a listing can be watched
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Availability {
+    #[default]
+    Available,
+    /// Listed as "Private video"
+    Private,
+    /// Listed as "Deleted video"
+    Deleted,
+    /// Listed without a privacy status for any other reason, e.g. blocked by copyright
+    Unavailable,
+}
+
+impl fmt::Display for Availability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Availability::Available => "available",
+            Availability::Private => "private",
+            Availability::Deleted => "deleted",
+            Availability::Unavailable => "unavailable",
+        })
+    }
+}
+
+/// Whether a video is a live broadcast, and its state if it is
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum LiveBroadcastContent {
+    #[default]
+    None,
+    Upcoming,
+    Live,
+}
+
+impl From<&str> for LiveBroadcastContent {
+    fn from(value: &str) -> Self {
+        match value {
+            "upcoming" => LiveBroadcastContent::Upcoming,
+            "live" => LiveBroadcastContent::Live,
+            _ => LiveBroadcastContent::None,
+        }
+    }
+}
+
+/// Schedule and audience of a video that is, was or will be live
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LiveStreamingDetails {
+    pub scheduled_start_time: Option<String>,
+    pub scheduled_end_time: Option<String>,
+    pub actual_start_time: Option<String>,
+    pub actual_end_time: Option<String>,
+    /// Only present while the stream is live
+    pub concurrent_viewers: Option<u64>,
+    /// Only present while the stream is live and its chat is enabled
+    pub active_live_chat_id: Option<String>,
+}
+
+impl LiveStreamingDetails {
+    /// Whether the stream has started and has not ended yet
+    pub fn is_live(&self) -> bool {
+        self.actual_start_time.is_some() && self.actual_end_time.is_none()
+    }
 }
 
 impl Video {
     pub fn new(
-        id: String,
+        id: VideoId,
         title: String,
         description: String,
         published_at: String,
-        thumbnail: String,
+        thumbnails: Thumbnails,
     ) -> Self {
         Self {
             id,
-            title,
-            description,
-            published_at,
-            thumbnail,
+            title: Some(title),
+            description: Some(description),
+            published_at: Some(published_at),
+            thumbnail: thumbnails.get(ThumbnailSize::High).url,
+            thumbnails,
+            channel_id: None,
+            channel_title: String::new(),
+            tags: Vec::new(),
+            category_id: None,
+            default_language: None,
+            default_audio_language: None,
+            live_broadcast_content: LiveBroadcastContent::None,
+            live_streaming_details: None,
+            availability: Availability::Available,
+            status: None,
+            statistics: None,
+            content_details: None,
         }
     }
 
+    pub fn is_available(&self) -> bool {
+        self.availability == Availability::Available
+    }
+
+    /// Whether the video can be watched from a region, given as an ISO 3166-1 alpha-2 code.
+    /// Videos without a status are assumed to be playable when they are available
+    pub fn is_playable_in(&self, region: &str) -> bool {
+        self.is_available()
+            && self
+                .status
+                .as_ref()
+                .map_or(true, |status| status.is_playable_in(region))
+    }
+
     /// Returns the url for this video
     pub fn url(&self) -> String {
         format!("https://www.youtube.com/watch?v={}", self.id)
     }
 
     pub async fn search_video_by_id<T: HttpClientTrait>(
-        name: String,
+        video_id: VideoId,
         client: Arc<T>,
-    ) -> Result<Self, VideoError> {
-        retrieve_video_by_id(&name, client)
+    ) -> Result<Self, Error> {
+        Video::search_video_by_id_with_options(video_id, &RequestOptions::default(), client).await
+    }
+
+    /// Retrieves a video with only the parts and fields of the options, the sections that
+    /// are not requested are `None`
+    pub async fn search_video_by_id_with_options<T: HttpClientTrait>(
+        video_id: VideoId,
+        options: &RequestOptions,
+        client: Arc<T>,
+    ) -> Result<Self, Error> {
+        retrieve_video_by_id(video_id.as_str(), options, client)
             .await
-            .map_err(|e| VideoError {
-                source: Some(e.into()),
-                msg: "Failed to get video".to_owned(),
-            })
+            .map_err(Error::from)
+    }
+
+    /// Retrieves many videos at once, 50 per request, along with the ids that do not exist
+    pub async fn search_videos_by_ids<T: HttpClientTrait>(
+        video_ids: &[VideoId],
+        client: Arc<T>,
+    ) -> Result<BatchLookup<VideoId, Video>, Error> {
+        Video::search_videos_by_ids_with_options(video_ids, &RequestOptions::default(), client)
+            .await
+    }
+
+    /// Retrieves many videos at once with only the parts and fields of the options
+    pub async fn search_videos_by_ids_with_options<T: HttpClientTrait>(
+        video_ids: &[VideoId],
+        options: &RequestOptions,
+        client: Arc<T>,
+    ) -> Result<BatchLookup<VideoId, Video>, Error> {
+        let fetch = |chunk: Vec<VideoId>| {
+            let client = client.clone();
+            async move {
+                let ids: Vec<&str> = chunk.iter().map(VideoId::as_str).collect();
+                retrieve_videos_by_ids(&ids, options, client)
+                    .await
+                    .map_err(Error::from)
+            }
+        };
+
+        batch::lookup(video_ids, fetch, |video| &video.id).await
+    }
+
+    /// Retrieves the channel that uploaded this video
+    pub async fn channel<T: HttpClientTrait>(&self, client: Arc<T>) -> Result<Channel, Error> {
+        let channel_id = self
+            .channel_id
+            .clone()
+            .ok_or_else(|| Error::InvalidInput("the video has no channel id".to_owned()))?;
+
+        Channel::from_id(channel_id, client).await
+    }
+
+    /// Retrieves a page of the comment threads of this video, fails with an `Error::Api`
+    /// whose reason is `commentsDisabled` when comments are turned off
+    pub async fn comment_threads<T: HttpClientTrait>(
+        &self,
+        options: &CommentThreadOptions,
+        client: Arc<T>,
+    ) -> Result<Page<CommentThread>, Error> {
+        retrieve_comment_threads(("videoId", self.id.as_str()), options, &client)
+            .await
+            .map_err(Error::from)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::create_client_with_responses;
+    use crate::error::ApiError;
+    use crate::test_utils::{
+        create_client_with_mock_responses, create_client_with_responses, MockResponse,
+    };
+    use crate::youtube::comment::{CommentOrder, TextFormat};
+    use crate::youtube::parts::Part;
+    use std::time::Duration;
 
     #[tokio::test]
     async fn search_video_by_id_succeeds_with_valid_id() {
@@ -83,27 +383,730 @@ mod tests {
         }"#;
 
         let client = create_client_with_responses(vec![response]).await;
-        let video = Video::search_video_by_id("dQw4w9WgXcQ".to_string(), client)
+        let video = Video::search_video_by_id("dQw4w9WgXcQ".parse().unwrap(), client)
             .await
             .ok()
             .unwrap();
 
-        assert_eq!(video.title, "Video Title");
-        assert_eq!(video.description, "Video Description");
-        assert_eq!(video.published_at, "2009-10-25T06:57:33Z");
+        assert_eq!(video.title.as_deref(), Some("Video Title"));
+        assert_eq!(video.description.as_deref(), Some("Video Description"));
+        assert_eq!(video.published_at.as_deref(), Some("2009-10-25T06:57:33Z"));
         assert_eq!(
             video.thumbnail,
             "https://i.ytimg.com/vi/dQw4w9WgXcQ/hqdefault.jpg"
         );
         assert_eq!(video.url(), "https://www.youtube.com/watch?v=dQw4w9WgXcQ");
+        assert_eq!(video.channel_id.unwrap(), "UCuAXFkgsw1L7xaCfnd5JJOw");
+    }
+
+    #[tokio::test]
+    async fn search_video_by_id_returns_extended_snippet() {
+        let response = r#"{
+            "items": [
+                {
+                    "snippet": {
+                        "publishedAt": "2009-10-25T06:57:33Z",
+                        "channelId": "UCuAXFkgsw1L7xaCfnd5JJOw",
+                        "channelTitle": "Rick Astley",
+                        "title": "Video Title",
+                        "description": "Video Description",
+                        "tags": ["rick astley", "never gonna give you up"],
+                        "categoryId": "10",
+                        "defaultLanguage": "en",
+                        "defaultAudioLanguage": "en-US",
+                        "liveBroadcastContent": "upcoming"
+                    }
+                }
+            ]
+        }"#;
+
+        let client = create_client_with_responses(vec![response]).await;
+        let video = Video::search_video_by_id("dQw4w9WgXcQ".parse().unwrap(), client)
+            .await
+            .unwrap();
+
+        assert_eq!(video.channel_title, "Rick Astley");
+        assert_eq!(video.tags, vec!["rick astley", "never gonna give you up"]);
+        assert_eq!(video.category_id.as_deref(), Some("10"));
+        assert_eq!(video.default_language.as_deref(), Some("en"));
+        assert_eq!(video.default_audio_language.as_deref(), Some("en-US"));
+        assert_eq!(video.live_broadcast_content, LiveBroadcastContent::Upcoming);
+    }
+
+    #[tokio::test]
+    async fn search_video_by_id_returns_status_and_restrictions() {
+        let response = r#"{
+            "items": [
+                {
+                    "snippet": {
+                        "publishedAt": "2009-10-25T06:57:33Z",
+                        "title": "Video Title",
+                        "description": "Video Description"
+                    },
+                    "status": {
+                        "uploadStatus": "processed",
+                        "privacyStatus": "unlisted",
+                        "license": "creativeCommon",
+                        "embeddable": false,
+                        "publicStatsViewable": true,
+                        "madeForKids": true
+                    },
+                    "contentDetails": {
+                        "duration": "PT3M33S",
+                        "regionRestriction": {
+                            "blocked": ["DE", "RU"]
+                        },
+                        "contentRating": {
+                            "ytRating": "ytAgeRestricted"
+                        }
+                    }
+                }
+            ]
+        }"#;
+
+        let client = create_client_with_responses(vec![response]).await;
+        let video = Video::search_video_by_id("dQw4w9WgXcQ".parse().unwrap(), client)
+            .await
+            .unwrap();
+        let status = video.status.clone().unwrap();
+
+        assert_eq!(status.privacy_status, PrivacyStatus::Unlisted);
+        assert_eq!(status.license, License::CreativeCommon);
+        assert!(!status.embeddable);
+        assert!(status.made_for_kids);
+        assert!(status.age_restricted);
+        assert_eq!(status.allowed_regions, None);
+        assert_eq!(status.blocked_regions, vec!["DE", "RU"]);
+        assert!(video.is_playable_in("US"));
+        assert!(!video.is_playable_in("de"));
+    }
+
+    #[tokio::test]
+    async fn missing_status_fields_have_the_default_values() {
+        let response = r#"{
+            "items": [
+                {
+                    "snippet": {
+                        "title": "Video Title"
+                    },
+                    "status": {}
+                }
+            ]
+        }"#;
+
+        let client = create_client_with_responses(vec![response]).await;
+        let video = Video::search_video_by_id("dQw4w9WgXcQ".parse().unwrap(), client)
+            .await
+            .unwrap();
+
+        assert_eq!(video.status.unwrap(), VideoStatus::default());
+        assert!(VideoStatus::default().embeddable);
+    }
+
+    #[test]
+    fn videos_are_only_playable_in_allowed_regions() {
+        let status = VideoStatus {
+            allowed_regions: Some(vec!["US".to_string(), "CA".to_string()]),
+            ..Default::default()
+        };
+        let mut video = Video::new(
+            "dQw4w9WgXcQ".parse().unwrap(),
+            "Video Title".to_string(),
+            "Video Description".to_string(),
+            "2009-10-25T06:57:33Z".to_string(),
+            Thumbnails::for_video("dQw4w9WgXcQ"),
+        );
+        assert!(video.is_playable_in("FR"));
+
+        video.status = Some(status);
+        assert!(video.is_playable_in("ca"));
+        assert!(!video.is_playable_in("FR"));
+
+        video.availability = Availability::Deleted;
+        assert!(!video.is_playable_in("US"));
+    }
+
+    #[tokio::test]
+    async fn search_video_by_id_returns_live_streaming_details() {
+        let response = r#"{
+            "items": [
+                {
+                    "snippet": {
+                        "publishedAt": "2023-09-21T17:02:18Z",
+                        "title": "Live stream",
+                        "description": "Live stream description",
+                        "liveBroadcastContent": "live"
+                    },
+                    "liveStreamingDetails": {
+                        "actualStartTime": "2023-09-21T17:05:00Z",
+                        "scheduledStartTime": "2023-09-21T17:00:00Z",
+                        "concurrentViewers": "1234",
+                        "activeLiveChatId": "live_chat_id"
+                    }
+                }
+            ]
+        }"#;
+
+        let client = create_client_with_responses(vec![response]).await;
+        let video = Video::search_video_by_id("dQw4w9WgXcQ".parse().unwrap(), client)
+            .await
+            .unwrap();
+        let details = video.live_streaming_details.unwrap();
+
+        assert!(details.is_live());
+        assert_eq!(
+            details.scheduled_start_time.as_deref(),
+            Some("2023-09-21T17:00:00Z")
+        );
+        assert_eq!(
+            details.actual_start_time.as_deref(),
+            Some("2023-09-21T17:05:00Z")
+        );
+        assert_eq!(details.actual_end_time, None);
+        assert_eq!(details.concurrent_viewers, Some(1234));
+        assert_eq!(details.active_live_chat_id.as_deref(), Some("live_chat_id"));
+    }
+
+    fn video_item(video_id: &str) -> String {
+        format!(
+            r#"{{
+                "id": "{}",
+                "snippet": {{
+                    "publishedAt": "2009-10-25T06:57:33Z",
+                    "title": "Title {}",
+                    "description": "Video Description"
+                }}
+            }}"#,
+            video_id, video_id
+        )
+    }
+
+    fn video() -> Video {
+        Video::new(
+            "dQw4w9WgXcQ".parse().unwrap(),
+            "Video Title".to_string(),
+            "Video Description".to_string(),
+            "2009-10-25T06:57:33Z".to_string(),
+            Thumbnails::for_video("dQw4w9WgXcQ"),
+        )
+    }
+
+    #[tokio::test]
+    async fn comment_threads_are_returned() {
+        let response = r#"{
+            "nextPageToken": "QURTSl9p",
+            "pageInfo": {"totalResults": 20, "resultsPerPage": 20},
+            "items": [
+                {
+                    "id": "UgzThread",
+                    "snippet": {
+                        "channelId": "UCuAXFkgsw1L7xaCfnd5JJOw",
+                        "videoId": "dQw4w9WgXcQ",
+                        "topLevelComment": {
+                            "id": "UgzThread",
+                            "snippet": {
+                                "authorDisplayName": "@someone",
+                                "authorProfileImageUrl": "https://yt3.ggpht.com/someone",
+                                "authorChannelId": {"value": "UC_x5XG1OV2P6uZZ5FSM9Ttw"},
+                                "textDisplay": "Never gonna give you up",
+                                "textOriginal": "",
+                                "likeCount": 42,
+                                "publishedAt": "2023-01-01T10:00:00Z",
+                                "updatedAt": "2023-01-02T10:00:00Z"
+                            }
+                        },
+                        "canReply": true,
+                        "totalReplyCount": 7
+                    },
+                    "replies": {
+                        "comments": [
+                            {
+                                "id": "UgzThread.reply",
+                                "snippet": {
+                                    "authorDisplayName": "@other",
+                                    "textDisplay": "Never gonna let you down",
+                                    "parentId": "UgzThread",
+                                    "likeCount": 1,
+                                    "publishedAt": "2023-01-03T10:00:00Z",
+                                    "updatedAt": "2023-01-03T10:00:00Z"
+                                }
+                            }
+                        ]
+                    }
+                }
+            ]
+        }"#;
+        let client = create_client_with_responses(vec![response]).await;
+        let options = CommentThreadOptions::default()
+            .with_order(CommentOrder::Relevance)
+            .with_search_terms("never gonna")
+            .with_text_format(TextFormat::PlainText)
+            .with_max_results(500)
+            .with_page_token("QURTSl9o");
+
+        let page = video()
+            .comment_threads(&options, client.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            client.requested_urls(),
+            vec!["https://yt.lemnoslife.com/noKey/commentThreads?part=snippet%2Creplies&videoId=dQw4w9WgXcQ&order=relevance&textFormat=plainText&searchTerms=never+gonna&maxResults=100&pageToken=QURTSl9o"]
+        );
+        assert_eq!(page.next_page_token.as_deref(), Some("QURTSl9p"));
+        assert_eq!(page.total_results, Some(20));
+        let thread = &page.items[0];
+        assert!(thread.can_reply);
+        assert_eq!(thread.comment.author_name, "@someone");
+        assert_eq!(
+            thread.comment.author_channel_id.as_ref().unwrap(),
+            "UC_x5XG1OV2P6uZZ5FSM9Ttw"
+        );
+        assert_eq!(thread.comment.text, "Never gonna give you up");
+        assert_eq!(thread.comment.text_original, None);
+        assert_eq!(thread.comment.like_count, 42);
+        assert_eq!(thread.comment.reply_count, 7);
+        assert!(thread.comment.is_edited());
+        assert_eq!(thread.replies.len(), 1);
+        assert!(thread.replies[0].is_reply());
+        assert_eq!(thread.replies[0].author_channel_id, None);
+        assert_eq!(thread.replies[0].video_id.as_ref().unwrap(), "dQw4w9WgXcQ");
+    }
+
+    #[tokio::test]
+    async fn comment_threads_fail_when_comments_are_disabled() {
+        let comments_disabled = r#"{"error": {
+            "code": 403,
+            "message": "The video has disabled comments.",
+            "errors": [{"reason": "commentsDisabled", "domain": "youtube.commentThread"}]
+        }}"#;
+        let client =
+            create_client_with_mock_responses(vec![MockResponse::new(403, comments_disabled)])
+                .await;
+
+        let result = video()
+            .comment_threads(&CommentThreadOptions::default(), client)
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(Error::Api(ApiError { reason: Some(reason), .. })) if reason == "commentsDisabled"
+        ));
+    }
+
+    #[tokio::test]
+    async fn invalid_comment_threads_of_a_page_are_skipped() {
+        let response = r#"{
+            "items": [
+                {
+                    "id": "UgzValid",
+                    "snippet": {
+                        "videoId": "dQw4w9WgXcQ",
+                        "topLevelComment": {
+                            "id": "UgzValid",
+                            "snippet": {"textDisplay": "Never gonna give you up"}
+                        }
+                    }
+                },
+                {
+                    "id": "UgzInvalid",
+                    "snippet": {"videoId": "dQw4w9WgXcQ"}
+                }
+            ]
+        }"#;
+        let client = create_client_with_responses(vec![response]).await;
+        let options = CommentThreadOptions {
+            max_results: Some(0),
+            ..Default::default()
+        };
+
+        let page = video()
+            .comment_threads(&options, client.clone())
+            .await
+            .unwrap();
+
+        assert!(client.requested_urls()[0].contains("&maxResults=1"));
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].id, "UgzValid");
+        assert_eq!(page.skipped.len(), 1);
+        assert_eq!(page.skipped[0].id.as_deref(), Some("UgzInvalid"));
+        assert_eq!(page.skipped[0].path, "items[1].snippet");
+    }
+
+    #[tokio::test]
+    async fn videos_are_searched_by_many_ids() {
+        let response = format!(
+            r#"{{"items": [{}, {}]}}"#,
+            video_item("9bZkp7q19f0"),
+            video_item("dQw4w9WgXcQ")
+        );
+        let client = create_client_with_responses(vec![&response]).await;
+        let ids: Vec<VideoId> = ["dQw4w9WgXcQ", "kJQP7kiw5Fk", "9bZkp7q19f0", "dQw4w9WgXcQ"]
+            .iter()
+            .map(|id| id.parse().unwrap())
+            .collect();
+
+        let batch = Video::search_videos_by_ids(&ids, client.clone())
+            .await
+            .unwrap();
+
+        let found: Vec<&str> = batch.found.iter().map(|video| video.id.as_str()).collect();
+        assert_eq!(found, vec!["dQw4w9WgXcQ", "9bZkp7q19f0", "dQw4w9WgXcQ"]);
+        assert_eq!(batch.found[1].title.as_deref(), Some("Title 9bZkp7q19f0"));
+        assert_eq!(batch.not_found, vec![ids[1].clone()]);
+        assert_eq!(
+            client.requested_urls(),
+            vec!["https://yt.lemnoslife.com/noKey/videos?part=snippet%2CliveStreamingDetails%2Cstatus%2CcontentDetails&id=dQw4w9WgXcQ%2CkJQP7kiw5Fk%2C9bZkp7q19f0&maxResults=3"]
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_items_of_a_batch_are_skipped() {
+        let response = format!(
+            r#"{{"items": [
+                {},
+                {{"id": "9bZkp7q19f0", "snippet": {{"title": 42}}}},
+                {{"snippet": {{"title": "No id"}}}}
+            ]}}"#,
+            video_item("dQw4w9WgXcQ")
+        );
+        let client = create_client_with_responses(vec![&response]).await;
+        let ids: Vec<VideoId> = ["dQw4w9WgXcQ", "9bZkp7q19f0", "kJQP7kiw5Fk"]
+            .iter()
+            .map(|id| id.parse().unwrap())
+            .collect();
+
+        let batch = Video::search_videos_by_ids(&ids, client).await.unwrap();
+
+        assert_eq!(batch.found.len(), 1);
+        assert_eq!(batch.not_found, vec![ids[2].clone()]);
+        assert_eq!(batch.skipped.len(), 2);
+        assert_eq!(batch.skipped[0].id, Some(ids[1].clone()));
+        assert_eq!(batch.skipped[0].path, "items[1].snippet.title");
+        assert_eq!(batch.skipped[1].id, None);
+        assert_eq!(batch.skipped[1].path, "items[2].id");
+    }
+
+    #[tokio::test]
+    async fn videos_are_searched_by_chunks_of_fifty_ids() {
+        let ids: Vec<VideoId> = (0..55)
+            .map(|index| format!("video{:06}", index).parse().unwrap())
+            .collect();
+        let items = |ids: &[VideoId]| {
+            let items: Vec<String> = ids.iter().map(|id| video_item(id.as_str())).collect();
+            format!(r#"{{"items": [{}]}}"#, items.join(","))
+        };
+        let first_chunk = items(&ids[..50]);
+        let second_chunk = items(&ids[50..54]);
+        let client = create_client_with_responses(vec![&second_chunk, &first_chunk]).await;
+
+        let batch = Video::search_videos_by_ids(&ids, client.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(client.requested_urls().len(), 2);
+        assert_eq!(batch.found.len(), 54);
+        assert!(batch
+            .found
+            .iter()
+            .zip(&ids)
+            .all(|(video, id)| &video.id == id));
+        assert_eq!(batch.not_found, vec![ids[54].clone()]);
+    }
+
+    #[tokio::test]
+    async fn search_video_by_id_requests_only_the_parts_of_the_options() {
+        let response = r#"{
+            "items": [
+                {
+                    "statistics": {
+                        "viewCount": "1500000000",
+                        "likeCount": "17000000"
+                    }
+                }
+            ]
+        }"#;
+        let client = create_client_with_responses(vec![response]).await;
+        let options =
+            RequestOptions::with_parts(&[Part::Statistics]).with_fields("items(statistics)");
+
+        let video = Video::search_video_by_id_with_options(
+            "dQw4w9WgXcQ".parse().unwrap(),
+            &options,
+            client.clone(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            client.requested_urls(),
+            vec!["https://yt.lemnoslife.com/noKey/videos?part=statistics&fields=items%28statistics%29&id=dQw4w9WgXcQ"]
+        );
+        assert_eq!(video.title, None);
+        assert_eq!(video.published_at, None);
+        assert_eq!(
+            video.statistics,
+            Some(VideoStatistics {
+                view_count: Some(1_500_000_000),
+                like_count: Some(17_000_000),
+                comment_count: None,
+            })
+        );
+        assert_eq!(video.status, None);
+        assert_eq!(video.content_details, None);
+        assert_eq!(video.live_streaming_details, None);
+    }
+
+    #[tokio::test]
+    async fn search_video_by_id_returns_content_details() {
+        let response = r#"{
+            "items": [
+                {
+                    "snippet": {"title": "Video Title"},
+                    "contentDetails": {
+                        "duration": "PT3M33S",
+                        "definition": "hd",
+                        "caption": "true",
+                        "licensedContent": true
+                    }
+                }
+            ]
+        }"#;
+        let client = create_client_with_responses(vec![response]).await;
+
+        let video = Video::search_video_by_id("dQw4w9WgXcQ".parse().unwrap(), client)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            video.content_details,
+            Some(VideoContentDetails {
+                duration: Some("PT3M33S".to_string()),
+                definition: Some("hd".to_string()),
+                caption: true,
+                licensed_content: true,
+            })
+        );
+        assert_eq!(video.statistics, None);
+    }
+
+    #[tokio::test]
+    async fn channel_of_a_video_is_retrieved() {
+        let mut video = Video::new(
+            "dQw4w9WgXcQ".parse().unwrap(),
+            "Video Title".to_string(),
+            "Video Description".to_string(),
+            "2009-10-25T06:57:33Z".to_string(),
+            Thumbnails::for_video("dQw4w9WgXcQ"),
+        );
+        video.channel_id = Some("UCuAXFkgsw1L7xaCfnd5JJOw".parse().unwrap());
+        let client = create_client_with_responses(vec![
+            r#"{
+                "items": [
+                    {
+                        "id": "UCuAXFkgsw1L7xaCfnd5JJOw",
+                        "snippet": {
+                            "title": "Rick Astley",
+                            "customUrl": "@rickastleyyt"
+                        }
+                    }
+                ]
+            }"#,
+        ])
+        .await;
+
+        let channel = video.channel(client).await.unwrap();
+
+        assert_eq!(channel.channel_id, "UCuAXFkgsw1L7xaCfnd5JJOw");
+        assert_eq!(channel.title, "Rick Astley");
+        assert_eq!(channel.handle.unwrap(), "rickastleyyt");
+    }
+
+    #[tokio::test]
+    async fn channel_of_a_video_without_channel_id_is_invalid() {
+        let client = create_client_with_responses(vec![]).await;
+
+        let result = video().channel(client.clone()).await;
+
+        assert!(matches!(result, Err(Error::InvalidInput(_))));
+        assert!(client.requested_urls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn search_video_by_id_returns_every_thumbnail_size() {
+        let response = r#"{
+            "items": [
+                {
+                    "snippet": {
+                        "publishedAt": "2009-10-25T06:57:33Z",
+                        "title": "Video Title",
+                        "description": "Video Description",
+                        "thumbnails": {
+                            "default": {
+                                "url": "https://i.ytimg.com/vi/dQw4w9WgXcQ/default.jpg",
+                                "width": 120,
+                                "height": 90
+                            },
+                            "medium": {
+                                "url": "https://i.ytimg.com/vi/dQw4w9WgXcQ/mqdefault.jpg",
+                                "width": 320,
+                                "height": 180
+                            },
+                            "standard": {
+                                "url": "https://i.ytimg.com/vi/dQw4w9WgXcQ/sddefault.jpg",
+                                "width": 640,
+                                "height": 480
+                            }
+                        }
+                    }
+                }
+            ]
+        }"#;
+
+        let client = create_client_with_responses(vec![response]).await;
+        let video = Video::search_video_by_id("dQw4w9WgXcQ".parse().unwrap(), client)
+            .await
+            .unwrap();
+
+        assert_eq!(video.thumbnails.iter().count(), 3);
+        assert!(video.thumbnails.maxres.is_none());
+        assert_eq!(
+            video.thumbnails.best().url,
+            "https://i.ytimg.com/vi/dQw4w9WgXcQ/sddefault.jpg"
+        );
+        assert_eq!(
+            video.thumbnail,
+            "https://i.ytimg.com/vi/dQw4w9WgXcQ/hqdefault.jpg"
+        );
+        assert_eq!(video.thumbnails.default.unwrap().width, 120);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn video_is_serialized_with_snake_case_fields() {
+        let mut video = Video::new(
+            "dQw4w9WgXcQ".parse().unwrap(),
+            "Video Title".to_string(),
+            "Video Description".to_string(),
+            "2009-10-25T06:57:33Z".to_string(),
+            Thumbnails::for_video("dQw4w9WgXcQ"),
+        );
+        video.live_broadcast_content = LiveBroadcastContent::Upcoming;
+
+        let json = serde_json::to_value(&video).unwrap();
+        assert_eq!(json["id"], "dQw4w9WgXcQ");
+        assert_eq!(json["published_at"], "2009-10-25T06:57:33Z");
+        assert_eq!(json["live_broadcast_content"], "upcoming");
+
+        let deserialized: Video = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized, video);
+        assert!(serde_json::from_str::<VideoId>(r#""invalid""#).is_err());
     }
 
     #[tokio::test]
     async fn search_video_by_id_fails_with_invalid_id() {
         let client = create_client_with_responses(vec!["{}"]).await;
-        let video = Video::search_video_by_id("invalid_id".to_string(), client).await;
+        let video = Video::search_video_by_id(VideoId::new_unchecked("invalid_id"), client).await;
+
+        assert!(matches!(video, Err(Error::Decode(_))));
+    }
+
+    #[tokio::test]
+    async fn search_video_by_id_decode_errors_have_context() {
+        let client =
+            create_client_with_responses(vec![r#"{"items": [{"snippet": {"title": 5}}]}"#]).await;
+
+        let error = Video::search_video_by_id("dQw4w9WgXcQ".parse().unwrap(), client)
+            .await
+            .unwrap_err();
+
+        match error {
+            Error::Decode(error) => {
+                assert_eq!(error.status, 200);
+                assert_eq!(error.path, "items[0].snippet.title");
+                assert_eq!(
+                    error.body_excerpt,
+                    r#"{"items": [{"snippet": {"title": 5}}]}"#
+                );
+                assert_eq!(
+                    error.url,
+                    "https://yt.lemnoslife.com/noKey/videos?part=snippet%2CliveStreamingDetails%2Cstatus%2CcontentDetails&id=dQw4w9WgXcQ"
+                );
+            }
+            _ => panic!("Expected a decode error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn search_video_by_id_returns_api_error_envelope() {
+        let client = create_client_with_responses(vec![
+            r#"{
+                "error": {
+                    "code": 403,
+                    "message": "The video identified by the videoId parameter cannot be accessed.",
+                    "errors": [{ "reason": "forbidden", "domain": "youtube.video" }]
+                }
+            }"#,
+        ])
+        .await;
+
+        let error = Video::search_video_by_id("dQw4w9WgXcQ".parse().unwrap(), client)
+            .await
+            .unwrap_err();
+
+        match error {
+            Error::Api(error) => {
+                assert_eq!(error.code, 403);
+                assert_eq!(error.reason.as_deref(), Some("forbidden"));
+                assert_eq!(error.domain.as_deref(), Some("youtube.video"));
+            }
+            _ => panic!("Expected an api error"),
+        }
+    }
 
-        assert!(video.is_err());
-        assert_eq!(video.err().unwrap().msg, "Failed to get video");
+    #[tokio::test]
+    async fn search_video_by_id_errors_are_classified() {
+        let quota_exceeded = r#"{
+            "error": {
+                "code": 403,
+                "message": "The request cannot be completed because you have exceeded your quota.",
+                "errors": [{ "reason": "quotaExceeded" }]
+            }
+        }"#;
+        let client = create_client_with_mock_responses(vec![
+            MockResponse::new(404, ""),
+            MockResponse::new(503, ""),
+            MockResponse::new(403, quota_exceeded),
+            MockResponse::new(429, "").with_header("Retry-After", "30"),
+        ])
+        .await;
+        let video_id: VideoId = "dQw4w9WgXcQ".parse().unwrap();
+
+        let error = Video::search_video_by_id(video_id.clone(), client.clone())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::RateLimited {
+                retry_after: Some(retry_after)
+            } if retry_after == Duration::from_secs(30)
+        ));
+        assert!(error.is_retryable());
+
+        let error = Video::search_video_by_id(video_id.clone(), client.clone())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::QuotaExceeded));
+        assert!(!error.is_retryable());
+
+        let error = Video::search_video_by_id(video_id.clone(), client.clone())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::Unavailable));
+
+        let error = Video::search_video_by_id(video_id, client)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::NotFound));
     }
 }